@@ -48,6 +48,8 @@ pub trait AuctionMock: storage::AuctionMockStorage {
             if err_code > 0 {
                 result_err_data.push(bls_key);
                 result_err_data.push(BoxedBytes::from(&[err_code][..]));
+            } else {
+                self.set_bls_status(bls_key.as_slice(), b"staked");
             }
         }
 
@@ -74,6 +76,8 @@ pub trait AuctionMock: storage::AuctionMockStorage {
             if err_code > 0 {
                 result_err_data.push(bls_key.clone());
                 result_err_data.push(BoxedBytes::from(&[err_code][..]));
+            } else {
+                self.set_bls_status(bls_key.as_slice(), b"unStaked");
             }
         }
 
@@ -88,6 +92,30 @@ pub trait AuctionMock: storage::AuctionMockStorage {
         self.unstake_endpoint(bls_keys)
     }
 
+    #[endpoint(unStakeTokens)]
+    fn unstake_tokens_endpoint(&self, amount: Self::BigUint) -> SCResult<()> {
+        require!(
+            !self.is_staking_failure(),
+            "auction smart contract deliberate error"
+        );
+
+        self.set_unstake_tokens(&amount);
+        Ok(())
+    }
+
+    #[endpoint(unBondTokens)]
+    fn unbond_tokens_endpoint(&self, amount: Self::BigUint) -> SCResult<()> {
+        require!(
+            !self.is_staking_failure(),
+            "auction smart contract deliberate error"
+        );
+
+        self.set_unbond_tokens(&amount);
+        self.send()
+            .direct_egld(&self.blockchain().get_caller(), &amount, b"unbond tokens");
+        Ok(())
+    }
+
     #[endpoint(unBond)]
     fn unbond_endpoint(
         &self,
@@ -106,6 +134,8 @@ pub trait AuctionMock: storage::AuctionMockStorage {
             if err_code > 0 {
                 result_err_data.push(bls_key.clone());
                 result_err_data.push(BoxedBytes::from(&[err_code][..]));
+            } else {
+                self.clear_bls_status(bls_key.as_slice());
             }
         }
 
@@ -142,4 +172,21 @@ pub trait AuctionMock: storage::AuctionMockStorage {
         self.set_unjailed(&bls_keys.into_vec());
         Ok(())
     }
+
+    /// Status of each node, as set by the last stake/unstake/unbond or by `setBlsStatus`.
+    #[endpoint(getBlsKeysStatus)]
+    fn get_bls_keys_status(
+        &self,
+        #[var_args] bls_keys: VarArgs<BoxedBytes>,
+    ) -> MultiResultVec<MultiResult2<BoxedBytes, BoxedBytes>> {
+        let mut result = Vec::new();
+        for bls_key in bls_keys.into_vec().into_iter() {
+            let mut status = self.get_bls_status(bls_key.as_slice());
+            if status.is_empty() {
+                status = BoxedBytes::from(&b"notStaked"[..]);
+            }
+            result.push((bls_key, status).into());
+        }
+        result.into()
+    }
 }
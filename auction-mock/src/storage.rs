@@ -22,6 +22,12 @@ pub trait AuctionMockStorage {
     #[storage_set("unStake_bls_key")]
     fn set_unstake_bls_key(&self, node_index: usize, bls_key: &[u8]);
 
+    #[storage_set("unStake_tokens")]
+    fn set_unstake_tokens(&self, amount: &Self::BigUint);
+
+    #[storage_set("unBond_tokens")]
+    fn set_unbond_tokens(&self, amount: &Self::BigUint);
+
     #[storage_set("unBond_bls_key")]
     fn set_unbond_bls_key(&self, node_index: usize, bls_key: &[u8]);
 
@@ -39,6 +45,16 @@ pub trait AuctionMockStorage {
     #[storage_get("bls_deliberate_error")]
     fn get_bls_deliberate_error(&self, bls_key: &[u8]) -> u8;
 
+    #[endpoint(setBlsStatus)]
+    #[storage_set("bls_status")]
+    fn set_bls_status(&self, bls_key: &[u8], status: &[u8]);
+
+    #[storage_get("bls_status")]
+    fn get_bls_status(&self, bls_key: &[u8]) -> BoxedBytes;
+
+    #[storage_clear("bls_status")]
+    fn clear_bls_status(&self, bls_key: &[u8]);
+
     #[storage_set("unJailed")]
     fn set_unjailed(&self, bls_keys: &[BLSKey]);
 }
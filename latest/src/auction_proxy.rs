@@ -2,6 +2,13 @@ elrond_wasm::imports!();
 
 use node_storage::types::{BLSKey, BLSSignature};
 
+/// Node statuses reported by `getBlsKeysStatus`.
+/// Any other status (e.g. queued) is not acted upon.
+pub const BLS_STATUS_STAKED: &[u8] = b"staked";
+pub const BLS_STATUS_JAILED: &[u8] = b"jailed";
+pub const BLS_STATUS_UNSTAKED: &[u8] = b"unStaked";
+pub const BLS_STATUS_NOT_STAKED: &[u8] = b"notStaked";
+
 #[elrond_wasm_derive::proxy]
 pub trait Auction {
     #[payable("EGLD")]
@@ -24,6 +31,12 @@ pub trait Auction {
         #[var_args] bls_keys: VarArgs<BLSKey>,
     ) -> SCResult<MultiResultVec<BoxedBytes>>;
 
+    #[endpoint(unStakeTokens)]
+    fn unstake_tokens(&self, amount: Self::BigUint);
+
+    #[endpoint(unBondTokens)]
+    fn unbond_tokens(&self, amount: Self::BigUint);
+
     #[endpoint(unBond)]
     fn unbond(&self, #[var_args] bls_keys: VarArgs<BLSKey>)
         -> SCResult<MultiResultVec<BoxedBytes>>;
@@ -37,4 +50,10 @@ pub trait Auction {
     #[payable("EGLD")]
     #[endpoint(unJail)]
     fn unjail(&self, #[var_args] bls_keys: VarArgs<BLSKey>);
+
+    #[endpoint(getBlsKeysStatus)]
+    fn get_bls_keys_status(
+        &self,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> MultiResultVec<MultiResult2<BLSKey, BoxedBytes>>;
 }
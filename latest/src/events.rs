@@ -1,3 +1,5 @@
+use node_storage::types::BLSKey;
+
 elrond_wasm::imports!();
 
 /// Contains all events that can be emitted by the contract.
@@ -10,23 +12,84 @@ pub trait EventsModule {
     fn unstake_event(&self, delegator: &Address, amount: &Self::BigUint);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000003")]
-    fn stake_node_ok_event(&self, _data: ());
+    fn stake_node_ok_event(&self, bls_keys: &[BLSKey]);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000004")]
-    fn stake_node_fail_event(&self, _reason: &[u8]);
+    fn stake_node_fail_event(&self, reason: &[u8], bls_keys: &[BLSKey]);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000005")]
-    fn unstake_node_ok_event(&self, _data: ());
+    fn unstake_node_ok_event(&self, bls_keys: &[BLSKey]);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000006")]
-    fn unstake_node_fail_event(&self, _reason: &[u8]);
+    fn unstake_node_fail_event(&self, reason: &[u8], bls_keys: &[BLSKey]);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000007")]
-    fn unbond_node_ok_event(&self, _data: ());
+    fn unbond_node_ok_event(&self, bls_keys: &[BLSKey]);
 
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000008")]
-    fn unbond_node_fail_event(&self, _reason: &[u8]);
+    fn unbond_node_fail_event(&self, reason: &[u8], bls_keys: &[BLSKey]);
 
+    /// `reward_address` is where the rewards were sent, see `getRewardAddress`.
     #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000009")]
-    fn claim_rewards_event(&self, user: &Address, amount: &Self::BigUint);
+    fn claim_rewards_event(&self, user: &Address, reward_address: &Address, amount: &Self::BigUint);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000a")]
+    fn unstake_tokens_ok_event(&self, amount: &Self::BigUint);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000b")]
+    fn unstake_tokens_fail_event(&self, reason: &[u8], amount: &Self::BigUint);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000c")]
+    fn restake_event(&self, delegator: &Address, amount: &Self::BigUint);
+
+    /// Status code returned by the auction contract for a node it rejected.
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000d")]
+    fn stake_node_status_event(&self, bls_key: &BLSKey, status: i32);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000e")]
+    fn set_n_blocks_before_unbond_event(&self, n_blocks_before_unbond: u64);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000000f")]
+    fn set_n_blocks_before_force_unstake_event(&self, n_blocks_before_force_unstake: u64);
+
+    // 0x10 is `fund_transformed_event`, declared in user-fund-storage next to the fund transformations.
+
+    /// EGLD returned by the auction contract for a `claimUnusedFunds` call.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000011")]
+    fn claim_unused_funds_event(&self, amount: &Self::BigUint);
+
+    /// Staking was halted, after the given number of failed stake callbacks in a row.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000012")]
+    fn staking_halted_event(&self, stake_failure_count: usize);
+
+    /// New value of `getStakedInAuction`, after nodes were staked or unbonded, or tokens were unstaked.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000013")]
+    fn staked_in_auction_event(&self, staked_in_auction: &Self::BigUint);
+
+    /// The main auction contract was replaced via `setAuctionContractAddress`.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000014")]
+    fn auction_contract_address_changed_event(&self, old_address: &Address, new_address: &Address);
+
+    /// Bootstrap mode was ended by the owner, the total delegation cap was lowered to the given value.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000015")]
+    fn bootstrap_mode_ended_event(&self, total_delegation_cap: &Self::BigUint);
+
+    /// Stake sent to the auction contract by `stakeUpTo`, and the stakeable funds left behind.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000016")]
+    fn stake_up_to_event(&self, amount_staked: &Self::BigUint, amount_left: &Self::BigUint);
+
+    /// Target amount of a `proportionalUnstake` call, with the ids of the nodes chosen to cover it as data.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000017")]
+    fn proportional_unstake_event(&self, target_amount: &Self::BigUint, node_ids: &[usize]);
+
+    /// Validator rewards that arrived since they were last logged, and the block nonce when they were noticed.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000018")]
+    fn rewards_received_event(&self, amount: &Self::BigUint, block_nonce: u64);
+
+    /// Tokens taken back from the auction contract via `unBondTokensAmount`.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000019")]
+    fn unbond_tokens_ok_event(&self, amount: &Self::BigUint);
+
+    #[legacy_event("0x000000000000000000000000000000000000000000000000000000000000001a")]
+    fn unbond_tokens_fail_event(&self, reason: &[u8], amount: &Self::BigUint);
 }
@@ -7,6 +7,7 @@ pub mod auction_proxy;
 // modules
 pub mod events;
 pub mod node_activation;
+pub mod owner_reserve;
 pub mod reset_checkpoint_endpoints;
 pub mod reset_checkpoint_state;
 pub mod reset_checkpoint_types;
@@ -1,5 +1,13 @@
 use crate::auction_proxy;
-use node_storage::types::{BLSKey, BLSSignature, BLSStatusMultiArg, NodeState};
+use crate::auction_proxy::{
+    BLS_STATUS_JAILED, BLS_STATUS_NOT_STAKED, BLS_STATUS_STAKED, BLS_STATUS_UNSTAKED,
+};
+use crate::settings::{UNSTAKE_ORDER_FIFO, UNSTAKE_ORDER_LIFO};
+use node_storage::types::{
+    BLSKey, BLSSignature, BLSStatusMultiArg, NodeState, STAKE_STATUS_ALREADY_STAKED,
+};
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
 
 elrond_wasm::imports!();
 
@@ -15,31 +23,52 @@ pub trait NodeActivationModule:
     + crate::rewards_state::RewardStateModule
     + crate::user_stake_state::UserStakeStateModule
     + crate::events::EventsModule
+    + crate::owner_reserve::OwnerReserveModule
 {
     #[proxy]
     fn auction_proxy(&self, to: Address) -> auction_proxy::Proxy<Self::SendApi>;
 
     /// Owner activates specific nodes.
+    /// `amount_to_stake` must be exactly the sum of the stakes of the nodes, see `getNodesStake`.
+    /// Returns the ids of the nodes sent to the auction contract, followed by the async call.
     #[endpoint(stakeNodes)]
     fn stake_nodes(
         &self,
         amount_to_stake: Self::BigUint,
         #[var_args] bls_keys: VarArgs<BLSKey>,
-    ) -> SCResult<AsyncCall<Self::SendApi>> {
-        only_owner!(self, "only owner allowed to stake nodes");
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to stake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
 
         require!(
             !self.is_bootstrap_mode(),
             "cannot stake nodes in bootstrap mode"
         );
 
+        require!(
+            !self.is_staking_halted(),
+            "staking halted after repeated auction failures"
+        );
+
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
         );
 
+        self.validate_num_nodes_per_tx(bls_keys.len())?;
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
         require!(
-            self.total_unprotected() >= amount_to_stake,
+            self.stakeable_funds() >= amount_to_stake,
             "not enough funds in contract to stake nodes"
         );
 
@@ -47,6 +76,7 @@ pub trait NodeActivationModule:
 
         let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
         let mut bls_keys_signatures: Vec<MultiArg2<BLSKey, BLSSignature>> = Vec::new();
+        let bl_nonce = self.blockchain().get_block_nonce();
 
         for bls_key in bls_keys.into_vec().into_iter() {
             let node_id = self.get_node_id(&bls_key);
@@ -62,9 +92,372 @@ pub trait NodeActivationModule:
             bls_keys_signatures.push((bls_key, bls_signature).into());
 
             self.set_node_state(node_id, NodeState::PendingActivation);
+            self.set_node_pending_activation_nonce(node_id, bl_nonce);
+        }
+
+        require!(
+            amount_to_stake == self.get_nodes_stake(&node_ids, &stake_per_node),
+            "amount to stake does not match the stake of the nodes"
+        );
+
+        self.perform_stake_nodes(node_ids, bls_keys_signatures.into(), amount_to_stake)
+    }
+
+    /// Owner activates at most `max_nodes` inactive nodes, as many as the funds allow, see `getFeeReserve`.
+    /// `max_nodes` is capped at `getMaxNodesPerTx`. Nodes are picked in id order. Useful to split activation over several transactions.
+    /// Only nodes belonging to the same auction contract as the first picked node are staked.
+    /// Returns the ids of the nodes sent to the auction contract, followed by the async call.
+    #[endpoint(stakeUpTo)]
+    fn stake_up_to(
+        &self,
+        max_nodes: usize,
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to stake nodes"
+        );
+
+        let max_nodes = core::cmp::min(max_nodes, self.get_max_nodes_per_tx());
+        self.perform_stake_up_to(max_nodes)
+    }
+
+    /// Same as `stakeUpTo`, with at most `getMaxNodesPerTx` nodes.
+    /// Anyone can call it while auto-activation is enabled, see `isAutoActivationEnabled`,
+    /// so that a keeper can put idle funds to work.
+    #[endpoint(stakeAllAvailable)]
+    fn stake_all_available(
+        &self,
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
+        require!(
+            self.caller_can_activate(),
+            "only owner or operator allowed to stake nodes"
+        );
+
+        self.perform_stake_up_to(self.get_max_nodes_per_tx())
+    }
+
+    fn perform_stake_up_to(
+        &self,
+        max_nodes: usize,
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_bootstrap_mode(),
+            "cannot stake nodes in bootstrap mode"
+        );
+
+        require!(
+            !self.is_staking_halted(),
+            "staking halted after repeated auction failures"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
+        self.validate_owner_stake_share()?;
+
+        let (node_ids, amount_to_stake) = self.find_stakeable_nodes(max_nodes, &stake_per_node);
+        require!(!node_ids.is_empty(), "no nodes could be staked");
+
+        let amount_left = &self.stakeable_funds() - &amount_to_stake;
+        self.stake_up_to_event(&amount_to_stake, &amount_left);
+
+        let mut bls_keys_signatures: Vec<MultiArg2<BLSKey, BLSSignature>> = Vec::new();
+        let bl_nonce = self.blockchain().get_block_nonce();
+        for &node_id in node_ids.iter() {
+            let bls_key = self.get_node_id_to_bls(node_id);
+            let bls_signature = self.get_node_signature(node_id);
+            bls_keys_signatures.push((bls_key, bls_signature).into());
+
+            self.set_node_state(node_id, NodeState::PendingActivation);
+            self.set_node_pending_activation_nonce(node_id, bl_nonce);
+        }
+
+        self.perform_stake_nodes(node_ids, bls_keys_signatures.into(), amount_to_stake)
+    }
+
+    /// Owner activates the inactive nodes of a group, see `setNodeGroup`.
+    /// At most `getMaxNodesPerTx` nodes are staked, lowest id first; call again for the rest.
+    /// The amount staked is the sum of the stakes of the nodes.
+    /// Returns the ids of the nodes sent to the auction contract, followed by the async call.
+    #[endpoint(stakeGroup)]
+    fn stake_group(
+        &self,
+        group_id: u32,
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to stake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_bootstrap_mode(),
+            "cannot stake nodes in bootstrap mode"
+        );
+
+        require!(
+            !self.is_staking_halted(),
+            "staking halted after repeated auction failures"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
+        let node_ids: Vec<usize> = self
+            .get_node_ids_of_group(group_id)
+            .into_iter()
+            .filter(|&node_id| self.get_node_state(node_id) == NodeState::Inactive)
+            .take(self.get_max_nodes_per_tx())
+            .collect();
+        require!(!node_ids.is_empty(), "no inactive nodes in group");
+
+        let amount_to_stake = self.get_nodes_stake(&node_ids, &stake_per_node);
+        require!(
+            self.stakeable_funds() >= amount_to_stake,
+            "not enough funds in contract to stake nodes"
+        );
+
+        self.validate_owner_stake_share()?;
+
+        let mut bls_keys_signatures: Vec<MultiArg2<BLSKey, BLSSignature>> = Vec::new();
+        let bl_nonce = self.blockchain().get_block_nonce();
+        for &node_id in node_ids.iter() {
+            let bls_key = self.get_node_id_to_bls(node_id);
+            let bls_signature = self.get_node_signature(node_id);
+            bls_keys_signatures.push((bls_key, bls_signature).into());
+
+            self.set_node_state(node_id, NodeState::PendingActivation);
+            self.set_node_pending_activation_nonce(node_id, bl_nonce);
+        }
+
+        self.perform_stake_nodes(node_ids, bls_keys_signatures.into(), amount_to_stake)
+    }
+
+    /// Dry run of node activation: how many inactive nodes could be staked right now,
+    /// and the total EGLD that would be sent to the auction contract.
+    /// Does not change any node state.
+    #[view(computeStakeableNodes)]
+    fn compute_stakeable_nodes(&self) -> MultiResult2<usize, Self::BigUint> {
+        let stake_per_node = self.get_stake_per_node();
+        if stake_per_node == 0 {
+            return (0, Self::BigUint::zero()).into();
+        }
+
+        let (node_ids, amount_to_stake) = self.find_stakeable_nodes(usize::MAX, &stake_per_node);
+        (node_ids.len(), amount_to_stake).into()
+    }
+
+    /// Stakeable funds that do not add up to a whole node, at the current stake per node.
+    /// Topping up by the stake per node minus this amount makes room for one more node.
+    /// Nodes with their own stake are not taken into account. Zero if the stake per node is not set.
+    #[view(getActivationRemainder)]
+    fn get_activation_remainder(&self) -> Self::BigUint {
+        let stake_per_node = self.get_stake_per_node();
+        if stake_per_node == 0 {
+            return Self::BigUint::zero();
+        }
+
+        self.stakeable_funds() % stake_per_node
+    }
+
+    /// How much more stake could be put to work with the nodes currently available:
+    /// the stake needed for all `Inactive` nodes, minus the stakeable funds already waiting to stake them.
+    /// Nodes in `PendingActivation` are not counted, their stake is already on the way to the auction contract.
+    /// Zero if the stake per node is not set or the stakeable funds cover all inactive nodes.
+    #[view(getRemainingStakeCapacity)]
+    fn get_remaining_stake_capacity(&self) -> Self::BigUint {
+        let inactive_node_ids: Vec<usize> = (1..=self.num_nodes().get())
+            .filter(|&node_id| self.get_node_state(node_id) == NodeState::Inactive)
+            .collect();
+        let inactive_nodes_stake =
+            self.get_nodes_stake(&inactive_node_ids, &self.get_stake_per_node());
+        let stakeable = self.stakeable_funds();
+        if inactive_nodes_stake > stakeable {
+            inactive_nodes_stake - stakeable
+        } else {
+            Self::BigUint::zero()
+        }
+    }
+
+    /// Stake required by the auction contract for the node:
+    /// the stake set with `addNodesWithStake`, or the global stake per node otherwise.
+    #[view(getNodeStake)]
+    fn get_node_stake_endpoint(&self, bls_key: BLSKey) -> OptionalResult<Self::BigUint> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            let stake_per_node = self.get_stake_per_node();
+            OptionalResult::Some(self.get_node_stake_or_default(node_id, &stake_per_node))
+        }
+    }
+
+    /// The amount `stakeNodes` expects for the given nodes: the sum of their stakes, see `getNodeStake`.
+    /// Yields nothing if any of the BLS keys is unknown.
+    #[view(getNodesStake)]
+    fn get_nodes_stake_endpoint(
+        &self,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> OptionalResult<Self::BigUint> {
+        let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(bls_key);
+            if node_id == 0 {
+                return OptionalResult::None;
+            }
+            node_ids.push(node_id);
+        }
+
+        OptionalResult::Some(self.get_nodes_stake(&node_ids, &self.get_stake_per_node()))
+    }
+
+    /// Same as `getNodeStake`, by node id. Zero for unknown node ids.
+    #[view(getNodeStakeRequirement)]
+    fn get_node_stake_requirement(&self, node_id: usize) -> Self::BigUint {
+        if node_id == 0 || node_id > self.num_nodes().get() {
+            return Self::BigUint::zero();
+        }
+
+        self.get_node_stake_or_default(node_id, &self.get_stake_per_node())
+    }
+
+    /// Stake required by the auction contract for all `Active` and `PendingActivation` nodes.
+    /// Compare with `getStakedInAuction` to detect a shortfall.
+    #[view(getTotalStakeRequirement)]
+    fn get_total_stake_requirement(&self) -> Self::BigUint {
+        let node_ids: Vec<usize> = (1..=self.num_nodes().get())
+            .filter(|&node_id| {
+                matches!(
+                    self.get_node_state(node_id),
+                    NodeState::Active | NodeState::PendingActivation
+                )
+            })
+            .collect();
+        self.get_nodes_stake(&node_ids, &self.get_stake_per_node())
+    }
+
+    /// The main settings in one call, all read at the same block.
+    /// Yields, in order:
+    /// - the main auction contract address,
+    /// - the stake per node,
+    /// - the number of blocks before unbond,
+    /// - the number of blocks before force unstake,
+    /// - whether the contract is in bootstrap mode,
+    /// - the number of nodes, including removed ones.
+    #[view(getContractConfig)]
+    fn get_contract_config(&self) -> MultiResult6<Address, Self::BigUint, u64, u64, bool, usize> {
+        (
+            self.get_auction_contract_address(),
+            self.get_stake_per_node(),
+            self.get_n_blocks_before_unbond(),
+            self.get_n_blocks_before_force_unstake(),
+            self.is_bootstrap_mode(),
+            self.num_nodes().get(),
+        )
+            .into()
+    }
+
+    /// Diagnostic, compares node states against the fund totals. Does not change any state.
+    /// Yields, in order:
+    /// - the stake of the `Active` nodes (sum of the stakes of the nodes),
+    /// - the total `Active` funds,
+    /// - the stake of the nodes in `UnBondPeriod`,
+    /// - the total `UnStaked` funds.
+    /// The pairs should be equal; a difference indicates that node states and funds drifted apart.
+    #[view(checkStateConsistency)]
+    fn check_state_consistency(
+        &self,
+    ) -> MultiResult4<Self::BigUint, Self::BigUint, Self::BigUint, Self::BigUint> {
+        let stake_per_node = self.get_stake_per_node();
+        let mut active_nodes_stake = Self::BigUint::zero();
+        let mut unbond_period_nodes_stake = Self::BigUint::zero();
+        let num_nodes = self.num_nodes().get();
+        for node_id in 1..=num_nodes {
+            match self.get_node_state(node_id) {
+                NodeState::Active => {
+                    active_nodes_stake += &self.get_node_stake_or_default(node_id, &stake_per_node);
+                }
+                NodeState::UnBondPeriod { .. } => {
+                    unbond_period_nodes_stake +=
+                        &self.get_node_stake_or_default(node_id, &stake_per_node);
+                }
+                _ => {}
+            }
+        }
+
+        (
+            active_nodes_stake,
+            self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active),
+            unbond_period_nodes_stake,
+            self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked),
+        )
+            .into()
+    }
+
+    /// Unprotected funds that can be used to stake nodes: everything except the fee reserve.
+    /// Funds owed to delegators are never included, since they are not unprotected:
+    /// unclaimed rewards, waiting stake, deferred payments and withdraw-only funds.
+    #[view(getStakeableFunds)]
+    fn stakeable_funds(&self) -> Self::BigUint {
+        let unprotected = self.total_unprotected();
+        let fee_reserve = self.get_fee_reserve();
+        if unprotected > fee_reserve {
+            unprotected - fee_reserve
+        } else {
+            Self::BigUint::zero()
+        }
+    }
+
+    /// Scans for at most `max_nodes` inactive nodes that can be covered by the stakeable funds.
+    /// Nodes are picked in id order, all from the same auction contract.
+    /// Nodes whose stake is more than the funds left are skipped.
+    /// Yields the node ids and the amount to be staked for them.
+    fn find_stakeable_nodes(
+        &self,
+        max_nodes: usize,
+        stake_per_node: &Self::BigUint,
+    ) -> (Vec<usize>, Self::BigUint) {
+        let mut stake_available = self.stakeable_funds();
+        let mut amount_to_stake = Self::BigUint::zero();
+        let mut node_ids = Vec::<usize>::new();
+
+        let num_nodes = self.num_nodes().get();
+        let mut auction_index = None;
+        let mut node_id = 1;
+        while node_id <= num_nodes && node_ids.len() < max_nodes && stake_available > 0 {
+            if self.get_node_state(node_id) == NodeState::Inactive {
+                let node_stake = self.get_node_stake_or_default(node_id, stake_per_node);
+                if node_stake <= stake_available
+                    && self.is_node_in_auction(node_id, &mut auction_index)
+                {
+                    node_ids.push(node_id);
+                    stake_available -= &node_stake;
+                    amount_to_stake += &node_stake;
+                }
+            }
+            node_id += 1;
         }
 
-        Ok(self.perform_stake_nodes(node_ids, bls_keys_signatures.into(), amount_to_stake))
+        (node_ids, amount_to_stake)
     }
 
     fn perform_stake_nodes(
@@ -72,72 +465,248 @@ pub trait NodeActivationModule:
         node_ids: Vec<usize>,
         bls_keys_signatures: VarArgs<MultiArg2<BLSKey, BLSSignature>>,
         amount_to_stake: Self::BigUint,
-    ) -> AsyncCall<Self::SendApi> {
+    ) -> SCResult<MultiResult2<MultiResultVec<usize>, AsyncCall<Self::SendApi>>> {
         let num_nodes = node_ids.len();
+        let stake_per_node = self.get_stake_per_node();
+        let node_stakes: Vec<Self::BigUint> = node_ids
+            .iter()
+            .map(|&node_id| self.get_node_stake_or_default(node_id, &stake_per_node))
+            .collect();
+
         // send all stake to auction contract
-        let auction_contract_addr = self.get_auction_contract_address();
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
+        let bl_nonce = self.blockchain().get_block_nonce();
 
-        self.auction_proxy(auction_contract_addr)
-            .with_token_transfer(TokenIdentifier::egld(), amount_to_stake)
+        let async_call = self
+            .auction_proxy(auction_contract_addr)
+            .with_token_transfer(self.get_staking_token(), amount_to_stake)
             .stake(num_nodes, bls_keys_signatures)
             .async_call()
-            .with_callback(self.callbacks().auction_stake_callback(node_ids))
+            .with_callback(self.callbacks().auction_stake_callback(
+                node_ids.clone(),
+                node_stakes,
+                bl_nonce,
+            ));
+
+        Ok((node_ids.into(), async_call).into())
     }
 
     /// Only finalize activation if we got confirmation from the auction contract.
+    /// `node_stakes` holds the stake sent for each node, in the order of `node_ids`.
+    /// Nodes whose activation was cancelled since `pending_since`, the nonce of the stake call, are ignored,
+    /// even if they were staked again in the meantime.
     /// `#[callback]` also has be declared in lib.rs for the moment.
     #[callback]
     fn auction_stake_callback(
         &self,
         node_ids: Vec<usize>,
+        node_stakes: Vec<Self::BigUint>,
+        pending_since: u64,
         #[call_result] call_result: AsyncCallResult<MultiResultVec<BLSStatusMultiArg>>,
     ) -> SCResult<()> {
+        let is_same_activation =
+            |node_id: &usize| self.get_node_pending_activation_nonce(*node_id) == pending_since;
         match call_result {
             AsyncCallResult::Ok(node_status_args) => {
-                let (node_ids_ok, node_ids_fail) =
+                let node_stakes: Vec<(usize, Self::BigUint)> = node_ids
+                    .iter()
+                    .cloned()
+                    .zip(node_stakes.into_iter())
+                    .collect();
+                let (mut node_ids_ok, node_ids_already_staked, node_statuses_fail) =
                     self.split_node_ids_by_err(node_ids, node_status_args);
-                self.auction_stake_callback_ok(node_ids_ok)?;
+                node_ids_ok.retain(is_same_activation);
+                let node_ids_already_staked: Vec<usize> = node_ids_already_staked
+                    .into_iter()
+                    .filter(is_same_activation)
+                    .collect();
+                let node_statuses_fail: Vec<(usize, i32)> = node_statuses_fail
+                    .into_iter()
+                    .filter(|(node_id, _)| is_same_activation(node_id))
+                    .collect();
+                // nodes that were already staked are live, the stake sent for them was kept
+                for &node_id in node_ids_already_staked.iter() {
+                    let bls_key = self.get_node_id_to_bls(node_id);
+                    self.stake_node_status_event(&bls_key, STAKE_STATUS_ALREADY_STAKED);
+                }
+                node_ids_ok.extend(node_ids_already_staked);
+                self.auction_stake_callback_ok(node_ids_ok, node_stakes.as_slice())?;
+                // the stake of the rejected nodes stays in the auction contract until claimed
                 self.auction_stake_callback_fail(
-                    node_ids_fail,
+                    node_statuses_fail
+                        .iter()
+                        .map(|&(node_id, _)| node_id)
+                        .collect(),
+                    NodeState::ActivationFailed,
                     &b"staking failed for some nodes"[..],
                 )?;
+                // also log why each node was rejected
+                for &(node_id, status) in node_statuses_fail.iter() {
+                    let bls_key = self.get_node_id_to_bls(node_id);
+                    self.stake_node_status_event(&bls_key, status);
+                }
                 Ok(())
             }
             AsyncCallResult::Err(error) => {
-                self.auction_stake_callback_fail(node_ids, error.err_msg.as_slice())
+                // the stake is returned with the failed async call
+                self.auction_stake_callback_fail(
+                    node_ids.into_iter().filter(is_same_activation).collect(),
+                    NodeState::Inactive,
+                    error.err_msg.as_slice(),
+                )
             }
         }
     }
 
-    fn auction_stake_callback_ok(&self, node_ids: Vec<usize>) -> SCResult<()> {
+    /// Each node records its own stake from `node_stakes`, see `getNodeStakedInAuction`.
+    fn auction_stake_callback_ok(
+        &self,
+        node_ids: Vec<usize>,
+        node_stakes: &[(usize, Self::BigUint)],
+    ) -> SCResult<()> {
+        let node_ids = self.filter_nodes_by_state(node_ids, |node_state| {
+            node_state == NodeState::PendingActivation
+        });
         if node_ids.is_empty() {
             return Ok(());
         }
 
-        // set nodes to Active
+        self.set_stake_failure_count(0);
+
+        // rewards so far go to the nodes that were already active
+        let node_reward_index = self.update_node_reward_index();
+
+        // set nodes to Active + reset their reward accrual
+        let bl_nonce = self.blockchain().get_block_nonce();
+        let mut staked = Self::BigUint::zero();
         for &node_id in node_ids.iter() {
             self.set_node_state(node_id, NodeState::Active);
+            self.set_node_activation_nonce(node_id, bl_nonce);
+            self.record_node_state_change(node_id);
+            self.set_node_reward_checkpoint(node_id, &node_reward_index);
+            if let Some((_, node_stake)) = node_stakes.iter().find(|(id, _)| *id == node_id) {
+                self.set_node_staked_in_auction(node_id, node_stake);
+                staked += node_stake;
+            }
         }
 
-        // log event (no data)
-        // TODO: log BLS keys of nodes in data
-        self.stake_node_ok_event(());
+        // log event, with the BLS keys of the affected nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.stake_node_ok_event(bls_keys.as_slice());
+
+        let mut staked_in_auction = self.get_staked_in_auction();
+        staked_in_auction += &staked;
+        self.update_staked_in_auction(&staked_in_auction);
+
+        Ok(())
+    }
+
+    fn auction_stake_callback_fail(
+        &self,
+        node_ids: Vec<usize>,
+        new_state: NodeState,
+        err_msg: &[u8],
+    ) -> SCResult<()> {
+        let node_ids = self.revert_pending_activation(node_ids, new_state, err_msg);
+        if !node_ids.is_empty() {
+            self.record_stake_failure();
+        }
 
         Ok(())
     }
 
-    fn auction_stake_callback_fail(&self, node_ids: Vec<usize>, err_msg: &[u8]) -> SCResult<()> {
+    /// Moves the nodes still in `PendingActivation` to `new_state`, any other node is skipped.
+    /// Yields the ids of the nodes reverted.
+    fn revert_pending_activation(
+        &self,
+        node_ids: Vec<usize>,
+        new_state: NodeState,
+        err_msg: &[u8],
+    ) -> Vec<usize> {
+        let node_ids = self.filter_nodes_by_state(node_ids, |node_state| {
+            node_state == NodeState::PendingActivation
+        });
         if node_ids.is_empty() {
-            return Ok(());
+            return node_ids;
         }
 
-        // set nodes to Inactive
+        // set nodes to Inactive/ActivationFailed
         for &node_id in node_ids.iter() {
-            self.set_node_state(node_id, NodeState::Inactive);
+            self.set_node_state(node_id, new_state);
+        }
+
+        // log failure event, with the BLS keys of the failed nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.stake_node_fail_event(err_msg, bls_keys.as_slice());
+
+        node_ids
+    }
+
+    fn update_staked_in_auction(&self, staked_in_auction: &Self::BigUint) {
+        self.set_staked_in_auction(staked_in_auction);
+        self.staked_in_auction_event(staked_in_auction);
+    }
+
+    /// Counts failed stake callbacks, cancelled activations are not counted,
+    /// and halts staking once the configured threshold is reached.
+    fn record_stake_failure(&self) {
+        let stake_failure_count = self.get_stake_failure_count() + 1;
+        self.set_stake_failure_count(stake_failure_count);
+
+        let threshold = self.get_stake_failure_threshold();
+        if threshold > 0 && stake_failure_count >= threshold && !self.is_staking_halted() {
+            self.set_staking_halted(true);
+            self.staking_halted_event(stake_failure_count);
         }
+    }
 
-        // log failure event (no data)
-        self.stake_node_fail_event(err_msg);
+    /// Reverts nodes stuck in `PendingActivation` back to `Inactive`,
+    /// for when the auction contract never called back.
+    /// Only allowed after `getNumBlocksBeforeCancelActivation` blocks since the stake call,
+    /// so that a callback that is still in flight is not raced.
+    /// No funds are reserved for pending nodes: stake returned by a failed call is unprotected again,
+    /// stake that did reach the auction contract can be retrieved with `claimUnusedFunds`.
+    /// A callback arriving after the cancel is ignored for these nodes.
+    #[endpoint(cancelPendingActivation)]
+    fn cancel_pending_activation(&self, #[var_args] bls_keys: VarArgs<BLSKey>) -> SCResult<()> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator can cancel pending activation"
+        );
+
+        let n_blocks_before_cancel = self.get_n_blocks_before_cancel_activation();
+        require!(
+            n_blocks_before_cancel > 0,
+            "cancelling pending activation is disabled"
+        );
+
+        let bl_nonce = self.blockchain().get_block_nonce();
+        let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
+        for bls_key in bls_keys.into_vec().into_iter() {
+            let node_id = self.get_node_id(&bls_key);
+            require!(node_id != 0, "unknown node provided");
+
+            require!(
+                self.get_node_state(node_id) == NodeState::PendingActivation,
+                "node not pending activation"
+            );
+
+            let pending_since = self.get_node_pending_activation_nonce(node_id);
+            require!(
+                pending_since
+                    .checked_add(n_blocks_before_cancel)
+                    .map_or(false, |cancel_nonce| bl_nonce >= cancel_nonce),
+                "too soon to cancel pending activation"
+            );
+
+            node_ids.push(node_id);
+        }
+
+        self.revert_pending_activation(
+            node_ids,
+            NodeState::Inactive,
+            &b"pending activation cancelled"[..],
+        );
 
         Ok(())
     }
@@ -168,28 +737,278 @@ pub trait NodeActivationModule:
         self.unstake_nodes(true, bls_keys)
     }
 
+    /// Owner deactivates the active nodes of a group, see `setNodeGroup`.
+    /// At most `getMaxNodesPerTx` nodes are unstaked, lowest id first; call again for the rest.
+    /// Also unstakes tokens, like `unStakeNodesAndTokens`.
+    #[endpoint(unStakeGroup)]
+    fn unstake_group(&self, group_id: u32) -> SCResult<AsyncCall<Self::SendApi>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unstake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let node_ids: Vec<usize> = self
+            .get_node_ids_of_group(group_id)
+            .into_iter()
+            .filter(|&node_id| self.get_node_state(node_id) == NodeState::Active)
+            .take(self.get_max_nodes_per_tx())
+            .collect();
+        require!(!node_ids.is_empty(), "no active nodes in group");
+
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.perform_unstake_nodes(true, node_ids, bls_keys)
+    }
+
     fn unstake_nodes(
         &self,
         unstake_tokens: bool,
         bls_keys: VarArgs<BLSKey>,
     ) -> SCResult<AsyncCall<Self::SendApi>> {
-        only_owner!(self, "only owner allowed to unstake nodes");
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unstake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
 
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
         );
 
+        self.validate_num_nodes_per_tx(bls_keys.len())?;
+
         let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
         for bls_key in bls_keys.iter() {
             let node_id = self.get_node_id(bls_key);
             require!(node_id != 0, "unknown node provided");
+            require!(!node_ids.contains(&node_id), "duplicate BLS key");
             node_ids.push(node_id);
         }
 
         self.perform_unstake_nodes(unstake_tokens, node_ids, bls_keys.into_vec())
     }
 
+    /// Same as `unStakeNodes`, but the nodes are given by id.
+    #[endpoint(unStakeNodesByIds)]
+    fn unstake_nodes_by_ids(
+        &self,
+        #[var_args] node_ids: VarArgs<usize>,
+    ) -> SCResult<AsyncCall<Self::SendApi>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unstake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        self.validate_num_nodes_per_tx(node_ids.len())?;
+
+        let num_nodes = self.num_nodes().get();
+        let node_ids = node_ids.into_vec();
+        let mut bls_keys = Vec::<BLSKey>::with_capacity(node_ids.len());
+        for (i, &node_id) in node_ids.iter().enumerate() {
+            require!(node_id > 0 && node_id <= num_nodes, "unknown node provided");
+            require!(!node_ids[..i].contains(&node_id), "duplicate node id");
+            bls_keys.push(self.get_node_id_to_bls(node_id));
+        }
+
+        self.perform_unstake_nodes(false, node_ids, bls_keys)
+    }
+
+    /// Delegators can unstake nodes themselves if their unstaked stake was not replaced
+    /// and the owner did not act for `n_blocks_before_force_unstake` blocks.
+    /// Unstakes active nodes covering at most the caller's unstaked stake
+    /// that was not already force unstaked, see `getUserForceUnstaked`,
+    /// and at most `getMaxNodesPerTx` nodes.
+    /// Also unstakes tokens.
+    #[endpoint(forceUnstake)]
+    fn force_unstake(&self) -> SCResult<AsyncCall<Self::SendApi>> {
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let user_id = self.get_user_id(&caller);
+        require!(user_id > 0, "unknown caller");
+
+        let n_blocks_before_force_unstake = self.get_n_blocks_before_force_unstake();
+        require!(n_blocks_before_force_unstake > 0, "force unstake disabled");
+
+        let user_unstaked = self.get_user_stake_of_type(user_id, FundType::UnStaked);
+        let request_nonce = self.get_user_unstake_request_nonce(user_id);
+        require!(
+            user_unstaked > 0 && request_nonce > 0,
+            "no unstaked stake to force out"
+        );
+
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        require!(
+            request_nonce
+                .checked_add(n_blocks_before_force_unstake)
+                .map_or(false, |force_nonce| current_bl_nonce >= force_nonce),
+            "too soon to force unstake"
+        );
+
+        let mut force_unstaked = self.get_user_force_unstaked(user_id);
+        require!(
+            force_unstaked < user_unstaked,
+            "unstaked stake already force unstaked"
+        );
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
+        // most recently added nodes go first, all from the same auction contract
+        // the nodes picked never hold more than the stake left to force out
+        let mut stake_left = &user_unstaked - &force_unstaked;
+        let max_nodes = self.get_max_nodes_per_tx();
+        let mut auction_index = None;
+        let mut node_ids = Vec::<usize>::new();
+        let mut bls_keys = Vec::<BLSKey>::new();
+        let mut node_id = self.num_nodes().get();
+        while node_id >= 1 && stake_left > 0 && node_ids.len() < max_nodes {
+            if self.get_node_state(node_id) == NodeState::Active {
+                let node_stake = self.get_node_stake_or_default(node_id, &stake_per_node);
+                if node_stake <= stake_left && self.is_node_in_auction(node_id, &mut auction_index)
+                {
+                    node_ids.push(node_id);
+                    bls_keys.push(self.get_node_id_to_bls(node_id));
+                    stake_left -= &node_stake;
+                    force_unstaked += &node_stake;
+                }
+            }
+
+            node_id -= 1;
+        }
+
+        require!(
+            !node_ids.is_empty(),
+            "not enough unstaked stake to force unstake a node"
+        );
+
+        self.set_user_force_unstaked(user_id, &force_unstaked);
+
+        // restart the countdown, to give the owner time to handle the unstaked nodes
+        self.set_user_unstake_request_nonce(user_id, current_bl_nonce);
+
+        self.perform_unstake_nodes(true, node_ids, bls_keys)
+    }
+
+    /// Owner unstakes the active nodes needed to cover the target amount,
+    /// in the order set with `setUnstakeNodeOrder`, lowest node id first by default.
+    /// Meant for an orderly wind-down, e.g. when slashing left the contract under-collateralized.
+    /// Fails if covering the target takes more than `getMaxNodesPerTx` nodes.
+    /// Only nodes from the same auction contract as the first chosen node are considered.
+    /// Also unstakes tokens.
+    #[endpoint(proportionalUnstake)]
+    fn proportional_unstake(
+        &self,
+        target_amount: Self::BigUint,
+    ) -> SCResult<AsyncCall<Self::SendApi>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unstake nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        require!(target_amount > 0, "target amount must be positive");
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
+        let mut stake_to_cover = target_amount.clone();
+        let max_nodes = self.get_max_nodes_per_tx();
+        let mut auction_index = None;
+        let mut node_ids = Vec::<usize>::new();
+        let mut bls_keys = Vec::<BLSKey>::new();
+        for node_id in self.get_active_nodes_in_unstake_order() {
+            if stake_to_cover == 0 || node_ids.len() == max_nodes {
+                break;
+            }
+
+            if self.is_node_in_auction(node_id, &mut auction_index) {
+                node_ids.push(node_id);
+                bls_keys.push(self.get_node_id_to_bls(node_id));
+                let node_stake = self.get_node_stake_or_default(node_id, &stake_per_node);
+                if stake_to_cover > node_stake {
+                    stake_to_cover -= &node_stake;
+                } else {
+                    stake_to_cover = Self::BigUint::zero();
+                }
+            }
+        }
+
+        require!(
+            stake_to_cover == 0 || node_ids.len() < max_nodes,
+            "target amount needs too many nodes in one transaction, see getMaxNodesPerTx"
+        );
+        require!(
+            stake_to_cover == 0,
+            "not enough active nodes to cover target amount"
+        );
+
+        self.proportional_unstake_event(&target_amount, node_ids.as_slice());
+
+        self.perform_unstake_nodes(true, node_ids, bls_keys)
+    }
+
+    /// Active node ids, ordered according to `getUnstakeNodeOrder`.
+    /// Nodes activated in the same block are ordered by id.
+    fn get_active_nodes_in_unstake_order(&self) -> Vec<usize> {
+        let mut node_ids: Vec<usize> = (1..=self.num_nodes().get())
+            .filter(|&node_id| self.get_node_state(node_id) == NodeState::Active)
+            .collect();
+        match self.get_unstake_node_order() {
+            UNSTAKE_ORDER_FIFO => {
+                node_ids.sort_by_key(|&node_id| self.get_node_activation_nonce(node_id));
+            }
+            UNSTAKE_ORDER_LIFO => {
+                node_ids.sort_by_key(|&node_id| {
+                    core::cmp::Reverse(self.get_node_activation_nonce(node_id))
+                });
+            }
+            _ => {}
+        }
+        node_ids
+    }
+
     fn perform_unstake_nodes(
         &self,
         unstake_tokens: bool,
@@ -207,7 +1026,7 @@ pub trait NodeActivationModule:
         }
 
         // send unstake command to Auction SC
-        let auction_contract_addr = self.get_auction_contract_address();
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
         let auction_proxy = self.auction_proxy(auction_contract_addr);
         if unstake_tokens {
             Ok(auction_proxy
@@ -222,80 +1041,425 @@ pub trait NodeActivationModule:
         }
     }
 
-    /// Only finalize deactivation if we got confirmation from the auction contract.
+    /// Only finalize deactivation if we got confirmation from the auction contract.
+    /// `#[callback]` also has be declared in lib.rs for the moment.
+    #[callback]
+    fn auction_unstake_callback(
+        &self,
+        node_ids: Vec<usize>,
+        #[call_result] call_result: AsyncCallResult<MultiResultVec<BLSStatusMultiArg>>,
+    ) -> SCResult<()> {
+        match call_result {
+            AsyncCallResult::Ok(node_status_args) => {
+                let (node_ids_ok, node_ids_already_staked, node_statuses_fail) =
+                    self.split_node_ids_by_err(node_ids, node_status_args);
+                self.auction_unstake_callback_ok(node_ids_ok)?;
+                self.auction_unstake_callback_fail(
+                    node_statuses_fail
+                        .iter()
+                        .map(|&(node_id, _)| node_id)
+                        .chain(node_ids_already_staked.into_iter())
+                        .collect(),
+                    &b"unstaking failed for some nodes"[..],
+                )?;
+                Ok(())
+            }
+            AsyncCallResult::Err(error) => {
+                self.auction_unstake_callback_fail(node_ids, error.err_msg.as_slice())
+            }
+        }
+    }
+
+    fn auction_unstake_callback_ok(&self, node_ids: Vec<usize>) -> SCResult<()> {
+        let node_ids = self.filter_nodes_by_state(node_ids, |node_state| {
+            node_state == NodeState::PendingDeactivation
+        });
+        if node_ids.is_empty() {
+            return Ok(());
+        }
+
+        // rewards so far go to the nodes that were active, including these ones
+        let _ = self.update_node_reward_index();
+
+        // set nodes to UnBondPeriod + save current block nonce
+        let bl_nonce = self.blockchain().get_block_nonce();
+        for &node_id in node_ids.iter() {
+            self.set_node_state(node_id, NodeState::UnBondPeriod { started: bl_nonce });
+            self.record_node_unstake(node_id);
+        }
+
+        // log event, with the BLS keys of the affected nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.unstake_node_ok_event(bls_keys.as_slice());
+
+        Ok(())
+    }
+
+    /// Owner can retry a callback in case of callback failure.
+    /// Warning: misuse can lead to state inconsistency.
+    #[endpoint(forceNodeUnBondPeriod)]
+    fn force_node_unbond_period(&self, #[var_args] bls_keys: VarArgs<BLSKey>) -> SCResult<()> {
+        only_owner!(self, "only owner can force nodes to unbond period");
+
+        let _ = self.update_node_reward_index();
+
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(bls_key);
+            require!(node_id != 0, "unknown node provided");
+            self.set_node_state(node_id, NodeState::UnBondPeriod { started: 0 });
+            self.record_node_unstake(node_id);
+        }
+
+        Ok(())
+    }
+
+    /// Recovery for when the stake of nodes was released too early,
+    /// e.g. after forcing them back to the unbond period.
+    /// Converts deferred payments back to unstaked funds, starting with the most recent ones.
+    /// Rejected if the reverted funds would break the delegation cap invariant,
+    /// see `validateDelegationCapInvariant`.
+    /// Only allowed during an emergency pause.
+    /// Warning: misuse can lead to state inconsistency.
+    #[endpoint(revertDeferredPaymentToUnStaked)]
+    fn revert_deferred_payment_to_unstaked(&self, amount: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can revert deferred payments");
+
+        require!(
+            self.is_emergency_paused(),
+            "can only revert deferred payments during emergency pause"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let total_deferred =
+            self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::DeferredPayment);
+        require!(
+            amount <= total_deferred,
+            "cannot revert more than the total deferred payments"
+        );
+
+        let mut remaining = amount;
+        self.revert_deferred_to_unstaked(&mut remaining, || false);
+
+        // active + unstaked must still match the delegation cap
+        self.validate_delegation_cap_invariant()
+    }
+
+    /// Recovery for lost auction callbacks: asks the auction contract for the status of the nodes,
+    /// then brings their local state in line with it, see `auction_sync_callback`.
+    /// Allowed for any node during an emergency pause.
+    /// Otherwise only for nodes pending activation for at least `getNumBlocksBeforeCancelActivation` blocks.
+    /// All nodes must belong to the same auction contract.
+    #[endpoint(syncNodeState)]
+    fn sync_node_state(
+        &self,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> SCResult<AsyncCall<Self::SendApi>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator can sync node state"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let emergency_paused = self.is_emergency_paused();
+        let n_blocks_before_cancel = self.get_n_blocks_before_cancel_activation();
+        let bl_nonce = self.blockchain().get_block_nonce();
+        let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(bls_key);
+            require!(node_id != 0, "unknown node provided");
+
+            if !emergency_paused {
+                require!(
+                    self.get_node_state(node_id) == NodeState::PendingActivation,
+                    "only nodes pending activation can be synced outside emergency pause"
+                );
+
+                let pending_since = self.get_node_pending_activation_nonce(node_id);
+                require!(
+                    n_blocks_before_cancel > 0
+                        && pending_since
+                            .checked_add(n_blocks_before_cancel)
+                            .map_or(false, |sync_nonce| bl_nonce >= sync_nonce),
+                    "too soon to sync node state"
+                );
+            }
+
+            node_ids.push(node_id);
+        }
+
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
+        Ok(self
+            .auction_proxy(auction_contract_addr)
+            .get_bls_keys_status(bls_keys)
+            .async_call()
+            .with_callback(self.callbacks().auction_sync_callback(node_ids)))
+    }
+
+    /// Reconciles the local node states with the statuses reported by the auction contract,
+    /// going through the same code as the regular callbacks:
+    /// - staked or jailed: `PendingActivation` and `PendingDeactivation` become `Active`;
+    /// - unstaked: `Active`, `PendingDeactivation` and `PendingUnBond` become `UnBondPeriod`;
+    /// - not staked: `PendingActivation`, `UnBondPeriod` and `PendingUnBond` become `Inactive`.
+    /// Any other node, or any node with another status, is left as it is.
+    /// `#[callback]` also has be declared in lib.rs for the moment.
+    #[callback]
+    fn auction_sync_callback(
+        &self,
+        node_ids: Vec<usize>,
+        #[call_result] call_result: AsyncCallResult<
+            MultiResultVec<MultiResult2<BLSKey, BoxedBytes>>,
+        >,
+    ) -> SCResult<()> {
+        let node_statuses = match call_result {
+            AsyncCallResult::Ok(node_statuses) => node_statuses,
+            AsyncCallResult::Err(_) => return Ok(()), // nothing learned, nothing to change
+        };
+
+        let stake_per_node = self.get_stake_per_node();
+        let mut stake_failed_ids = Vec::<usize>::new();
+        let mut unstake_ok_ids = Vec::<usize>::new();
+        let mut unstake_failed_ids = Vec::<usize>::new();
+        let mut unbond_ok_ids = Vec::<usize>::new();
+        let mut unbond_failed_ids = Vec::<usize>::new();
+        for arg in node_statuses.into_vec().into_iter() {
+            let (bls_key, status) = arg.into_tuple();
+            let node_id = self.get_node_id(&bls_key);
+            if !node_ids.contains(&node_id) {
+                continue;
+            }
+
+            let node_state = self.get_node_state(node_id);
+            match status.as_slice() {
+                BLS_STATUS_STAKED | BLS_STATUS_JAILED => match node_state {
+                    NodeState::PendingActivation => {
+                        let node_stake = self.get_node_stake_or_default(node_id, &stake_per_node);
+                        self.auction_stake_callback_ok(
+                            [node_id].to_vec(),
+                            &[(node_id, node_stake)],
+                        )?;
+                    }
+                    NodeState::PendingDeactivation => unstake_failed_ids.push(node_id),
+                    _ => {}
+                },
+                BLS_STATUS_UNSTAKED => match node_state {
+                    NodeState::Active => {
+                        // as if the unstake call had been sent
+                        self.set_node_state(node_id, NodeState::PendingDeactivation);
+                        unstake_ok_ids.push(node_id);
+                    }
+                    NodeState::PendingDeactivation => unstake_ok_ids.push(node_id),
+                    NodeState::PendingUnBond { .. } => unbond_failed_ids.push(node_id),
+                    _ => {}
+                },
+                BLS_STATUS_NOT_STAKED => match node_state {
+                    NodeState::PendingActivation => stake_failed_ids.push(node_id),
+                    NodeState::UnBondPeriod { started } => {
+                        // as if the unbond call had been sent
+                        self.set_node_state(
+                            node_id,
+                            NodeState::PendingUnBond {
+                                unbond_started: started,
+                            },
+                        );
+                        unbond_ok_ids.push(node_id);
+                    }
+                    NodeState::PendingUnBond { .. } => unbond_ok_ids.push(node_id),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        let sync_msg = &b"node state synced with auction contract"[..];
+        self.auction_stake_callback_fail(stake_failed_ids, NodeState::Inactive, sync_msg)?;
+        self.auction_unstake_callback_ok(unstake_ok_ids)?;
+        self.auction_unstake_callback_fail(unstake_failed_ids, sync_msg)?;
+        self.auction_unbond_callback_ok(unbond_ok_ids)?;
+        self.auction_unbond_callback_fail(unbond_failed_ids, sync_msg)
+    }
+
+    fn record_node_unstake(&self, node_id: usize) {
+        let unstake_count = self.get_node_unstake_count(node_id);
+        self.set_node_unstake_count(node_id, unstake_count + 1);
+        self.record_node_state_change(node_id);
+    }
+
+    fn auction_unstake_callback_fail(&self, node_ids: Vec<usize>, err_msg: &[u8]) -> SCResult<()> {
+        if node_ids.is_empty() {
+            return Ok(());
+        }
+
+        // revert nodes to Active
+        for &node_id in node_ids.iter() {
+            self.set_node_state(node_id, NodeState::Active);
+        }
+
+        // log failure event, with the BLS keys of the failed nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.unstake_node_fail_event(err_msg, bls_keys.as_slice());
+
+        Ok(())
+    }
+
+    /// Unstakes a specific amount of tokens from the auction smart contract, without deactivating any node.
+    /// The amount must be covered by the stake delegators already asked to unstake,
+    /// i.e. UnStaked funds not yet unstaked from the auction contract, see `getUnStakedTokens`.
+    /// No funds change type, the tokens are no longer counted in `getStakedInAuction`
+    /// and come back to the contract via `unBondTokensAmount`.
+    /// Tokens are always unstaked from the main auction contract.
+    /// This operation is performed by the owner.
+    #[endpoint(unStakeTokensAmount)]
+    fn unstake_tokens_amount(&self, amount: Self::BigUint) -> SCResult<AsyncCall<Self::SendApi>> {
+        only_owner!(self, "only owner allowed to unstake tokens");
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        require!(amount > 0, "cannot unstake zero tokens");
+        let total_unstaked = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+        let unstaked_tokens = self.get_unstaked_tokens();
+        require!(
+            total_unstaked >= unstaked_tokens && amount <= &total_unstaked - &unstaked_tokens,
+            "cannot unstake more tokens than the stake delegators asked to unstake"
+        );
+
+        // send unstake tokens command to Auction SC
+        let auction_contract_addr = self.get_auction_contract_address();
+        Ok(self
+            .auction_proxy(auction_contract_addr)
+            .unstake_tokens(amount.clone())
+            .async_call()
+            .with_callback(self.callbacks().auction_unstake_tokens_callback(amount)))
+    }
+
+    /// Only account for the unstaked tokens if we got confirmation from the auction contract.
+    /// `#[callback]` also has be declared in lib.rs for the moment.
+    #[callback]
+    fn auction_unstake_tokens_callback(
+        &self,
+        amount: Self::BigUint,
+        #[call_result] call_result: AsyncCallResult<()>,
+    ) -> SCResult<()> {
+        match call_result {
+            AsyncCallResult::Ok(()) => {
+                self.set_unstaked_tokens(&(self.get_unstaked_tokens() + amount.clone()));
+                self.set_unstaked_tokens_nonce(self.blockchain().get_block_nonce());
+
+                self.unstake_tokens_ok_event(&amount);
+
+                // the tokens left the auction contract
+                let staked_in_auction = self.get_staked_in_auction();
+                if staked_in_auction > amount {
+                    self.update_staked_in_auction(&(staked_in_auction - amount));
+                } else {
+                    self.update_staked_in_auction(&Self::BigUint::zero());
+                }
+
+                Ok(())
+            }
+            AsyncCallResult::Err(error) => {
+                self.unstake_tokens_fail_event(error.err_msg.as_slice(), &amount);
+                Ok(())
+            }
+        }
+    }
+
+    /// Takes back from the auction smart contract tokens unstaked via `unStakeTokensAmount`,
+    /// once `getNumBlocksBeforeUnBond` blocks have passed since tokens were last unstaked.
+    /// When the tokens arrive, the same amount of UnStaked funds becomes deferred payments
+    /// and the total delegation cap is lowered accordingly, as the stake left the contract.
+    /// This operation is performed by the owner.
+    #[endpoint(unBondTokensAmount)]
+    fn unbond_tokens_amount(&self, amount: Self::BigUint) -> SCResult<AsyncCall<Self::SendApi>> {
+        only_owner!(self, "only owner allowed to unbond tokens");
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        require!(amount > 0, "cannot unbond zero tokens");
+        require!(
+            amount <= self.get_unstaked_tokens(),
+            "cannot unbond more tokens than were unstaked"
+        );
+        require!(
+            self.blockchain().get_block_nonce()
+                >= self
+                    .get_unstaked_tokens_nonce()
+                    .saturating_add(self.get_n_blocks_before_unbond()),
+            "too soon to unbond tokens"
+        );
+
+        // send unbond tokens command to Auction SC
+        let auction_contract_addr = self.get_auction_contract_address();
+        Ok(self
+            .auction_proxy(auction_contract_addr)
+            .unbond_tokens(amount.clone())
+            .async_call()
+            .with_callback(self.callbacks().auction_unbond_tokens_callback(amount)))
+    }
+
+    /// Only release the UnStaked funds if we got confirmation from the auction contract.
     /// `#[callback]` also has be declared in lib.rs for the moment.
     #[callback]
-    fn auction_unstake_callback(
+    fn auction_unbond_tokens_callback(
         &self,
-        node_ids: Vec<usize>,
-        #[call_result] call_result: AsyncCallResult<MultiResultVec<BLSStatusMultiArg>>,
+        amount: Self::BigUint,
+        #[call_result] call_result: AsyncCallResult<()>,
     ) -> SCResult<()> {
         match call_result {
-            AsyncCallResult::Ok(node_status_args) => {
-                let (node_ids_ok, node_ids_fail) =
-                    self.split_node_ids_by_err(node_ids, node_status_args);
-                self.auction_unstake_callback_ok(node_ids_ok)?;
-                self.auction_unstake_callback_fail(
-                    node_ids_fail,
-                    &b"unstaking failed for some nodes"[..],
-                )?;
+            AsyncCallResult::Ok(()) => {
+                let unstaked_tokens = self.get_unstaked_tokens();
+                require!(
+                    amount <= unstaked_tokens,
+                    "cannot unbond more tokens than were unstaked"
+                );
+                self.set_unstaked_tokens(&(unstaked_tokens - amount.clone()));
+
+                // UnStaked -> DeferredPayment, the delegators can claim the returned tokens once matured
+                // UnStaked funds replaced by waiting stake in the meantime are already deferred payments
+                let total_unstaked =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+                let mut to_defer = core::cmp::min(&amount, &total_unstaked).clone();
+                let released = to_defer.clone();
+                self.swap_unstaked_to_deferred_payment(&mut to_defer, || false);
+                require!(to_defer == 0, "error swapping unstaked to deferred payment");
+
+                // keeps active + unstaked == total delegation cap
+                let total_delegation_cap = self.get_total_delegation_cap();
+                self.set_total_delegation_cap(total_delegation_cap - released);
+
+                self.unbond_tokens_ok_event(&amount);
                 Ok(())
             }
             AsyncCallResult::Err(error) => {
-                self.auction_unstake_callback_fail(node_ids, error.err_msg.as_slice())
+                self.unbond_tokens_fail_event(error.err_msg.as_slice(), &amount);
+                Ok(())
             }
         }
     }
 
-    fn auction_unstake_callback_ok(&self, node_ids: Vec<usize>) -> SCResult<()> {
-        if node_ids.is_empty() {
-            return Ok(());
-        }
-
-        // set nodes to UnBondPeriod + save current block nonce
-        let bl_nonce = self.blockchain().get_block_nonce();
-        for &node_id in node_ids.iter() {
-            self.set_node_state(node_id, NodeState::UnBondPeriod { started: bl_nonce });
-        }
-
-        // log event (no data)
-        // TODO: log BLS keys of nodes in data
-        self.unstake_node_ok_event(());
-
-        Ok(())
-    }
-
-    /// Owner can retry a callback in case of callback failure.
-    /// Warning: misuse can lead to state inconsistency.
-    #[endpoint(forceNodeUnBondPeriod)]
-    fn force_node_unbond_period(&self, #[var_args] bls_keys: VarArgs<BLSKey>) -> SCResult<()> {
-        only_owner!(self, "only owner can force nodes to unbond period");
-
-        for bls_key in bls_keys.iter() {
-            let node_id = self.get_node_id(bls_key);
-            require!(node_id != 0, "unknown node provided");
-            self.set_node_state(node_id, NodeState::UnBondPeriod { started: 0 });
-        }
-
-        Ok(())
-    }
-
-    fn auction_unstake_callback_fail(&self, node_ids: Vec<usize>, err_msg: &[u8]) -> SCResult<()> {
-        if node_ids.is_empty() {
-            return Ok(());
-        }
-
-        // revert nodes to Active
-        for &node_id in node_ids.iter() {
-            self.set_node_state(node_id, NodeState::Active);
-        }
-
-        // log failure event (no data)
-        self.unstake_node_fail_event(err_msg);
-
-        Ok(())
-    }
-
     // UNBOND
     /// Calls unbond for all provided nodes. Will fail if node cannot be unbonded.
     #[endpoint(unBondNodes)]
@@ -303,7 +1467,15 @@ pub trait NodeActivationModule:
         &self,
         #[var_args] bls_keys: VarArgs<BLSKey>,
     ) -> SCResult<AsyncCall<Self::SendApi>> {
-        only_owner!(self, "only owner allowed to unbond nodes");
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unbond nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
 
         require!(
             !self.is_global_op_in_progress(),
@@ -311,6 +1483,7 @@ pub trait NodeActivationModule:
         );
 
         require!(!bls_keys.is_empty(), "no BLS keys provided");
+        self.validate_num_nodes_per_tx(bls_keys.len())?;
 
         let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
         for bls_key in bls_keys.iter() {
@@ -323,25 +1496,40 @@ pub trait NodeActivationModule:
             node_ids.push(node_id);
         }
 
-        Ok(self.perform_unbond(node_ids, bls_keys.into_vec()))
+        self.perform_unbond(node_ids, bls_keys.into_vec())
     }
 
-    /// Calls unbond for all nodes that are in the unbond period and are due.
+    /// Calls unbond for all nodes that are in the unbond period and are due,
+    /// at most `getMaxNodesPerTx` of them, highest id first.
     /// Nothing happens if no nodes can be unbonded.
+    /// Only nodes belonging to the same auction contract as the first due node are unbonded.
     #[endpoint(unBondAllPossibleNodes)]
     fn unbond_all_possible_nodes(&self) -> SCResult<OptionalResult<AsyncCall<Self::SendApi>>> {
-        only_owner!(self, "only owner allowed to unbond nodes");
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unbond nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
 
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
         );
 
+        let max_nodes = self.get_max_nodes_per_tx();
+        let mut auction_index = None;
         let mut node_id = self.num_nodes().get();
         let mut node_ids = Vec::<usize>::new();
         let mut bls_keys = Vec::<BLSKey>::new();
-        while node_id >= 1 {
-            if self.prepare_node_for_unbond_if_possible(node_id) {
+        while node_id >= 1 && node_ids.len() < max_nodes {
+            if matches!(self.get_node_state(node_id), NodeState::UnBondPeriod { .. })
+                && self.is_node_in_auction(node_id, &mut auction_index)
+                && self.prepare_node_for_unbond_if_possible(node_id)
+            {
                 node_ids.push(node_id);
                 bls_keys.push(self.get_node_id_to_bls(node_id));
             }
@@ -354,10 +1542,158 @@ pub trait NodeActivationModule:
         }
 
         Ok(OptionalResult::Some(
-            self.perform_unbond(node_ids, bls_keys),
+            self.perform_unbond(node_ids, bls_keys)?,
         ))
     }
 
+    /// Same as `unBondAllPossibleNodes`, but only scans the nodes with ids from `start_id`,
+    /// at most `max_to_process` of them, to keep the gas cost bounded.
+    /// The scan also stops once `getMaxNodesPerTx` nodes were picked.
+    /// Yields the id to start the next call from, zero once all nodes were scanned, followed by the async call, if any.
+    /// To unbond all possible nodes, start with id 1 and keep calling with the returned id until it is zero.
+    /// The scan stops early at the first node to unbond from another auction contract than the previous ones,
+    /// so that the next call starts with it.
+    #[endpoint(unBondSomePossibleNodes)]
+    fn unbond_some_possible_nodes(
+        &self,
+        start_id: usize,
+        max_to_process: usize,
+    ) -> SCResult<MultiResult2<usize, OptionalResult<AsyncCall<Self::SendApi>>>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unbond nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        require!(max_to_process > 0, "max to process must be positive");
+
+        let num_nodes = self.num_nodes().get();
+        let max_nodes = self.get_max_nodes_per_tx();
+        let mut auction_index = None;
+        let mut node_ids = Vec::<usize>::new();
+        let mut bls_keys = Vec::<BLSKey>::new();
+        let mut node_id = core::cmp::max(start_id, 1);
+        let mut num_processed = 0;
+        while node_id <= num_nodes && num_processed < max_to_process && node_ids.len() < max_nodes {
+            if matches!(self.get_node_state(node_id), NodeState::UnBondPeriod { .. }) {
+                if !self.is_node_in_auction(node_id, &mut auction_index) {
+                    break;
+                }
+                if self.prepare_node_for_unbond_if_possible(node_id) {
+                    node_ids.push(node_id);
+                    bls_keys.push(self.get_node_id_to_bls(node_id));
+                }
+            }
+
+            node_id += 1;
+            num_processed += 1;
+        }
+
+        let next_start_id = if node_id > num_nodes { 0 } else { node_id };
+        if node_ids.is_empty() {
+            return Ok((next_start_id, OptionalResult::None).into());
+        }
+
+        let async_call = self.perform_unbond(node_ids, bls_keys)?;
+        Ok((next_start_id, OptionalResult::Some(async_call)).into())
+    }
+
+    /// Lists the nodes in the unbond period for at least `getNumBlocksBeforeUnBond` blocks.
+    /// Does not change any node state.
+    /// Nodes of all auction contracts are listed,
+    /// whereas `unBondAllPossibleNodes` only picks those of one auction contract per call.
+    #[view(getUnbondableNodes)]
+    fn get_unbondable_nodes(&self) -> MultiResultVec<BLSKey> {
+        let bl_nonce = self.blockchain().get_block_nonce();
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let num_nodes = self.num_nodes().get();
+        let mut result = Vec::<BLSKey>::new();
+        for node_id in 1..=num_nodes {
+            if let NodeState::UnBondPeriod { started } = self.get_node_state(node_id) {
+                // overflow means the unbond period never ends
+                let unbondable = started
+                    .checked_add(n_blocks_before_unbond)
+                    .map_or(false, |unbond_nonce| bl_nonce >= unbond_nonce);
+                if unbondable {
+                    result.push(self.get_node_id_to_bls(node_id));
+                }
+            }
+        }
+        result.into()
+    }
+
+    /// The earliest block nonce at which the node can be unbonded,
+    /// i.e. when it entered `UnBondPeriod` plus `getNumBlocksBeforeUnBond`.
+    /// Zero for nodes that are not in the unbond period.
+    #[view(getNodeUnbondEta)]
+    fn get_node_unbond_eta(&self, node_id: usize) -> u64 {
+        if node_id == 0 || node_id > self.num_nodes().get() {
+            return 0;
+        }
+
+        self.node_unbond_eta(node_id, self.get_n_blocks_before_unbond())
+    }
+
+    /// Same as `getNodeUnbondEta`, for all nodes in the unbond period.
+    /// Yields pairs of BLS key and earliest unbond block nonce.
+    #[view(getAllNodeUnbondEtas)]
+    fn get_all_node_unbond_etas(&self) -> MultiResultVec<MultiResult2<BLSKey, u64>> {
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let num_nodes = self.num_nodes().get();
+        let mut result = Vec::<MultiResult2<BLSKey, u64>>::new();
+        for node_id in 1..=num_nodes {
+            let unbond_eta = self.node_unbond_eta(node_id, n_blocks_before_unbond);
+            if unbond_eta > 0 {
+                result.push((self.get_node_id_to_bls(node_id), unbond_eta).into());
+            }
+        }
+        result.into()
+    }
+
+    /// Stake of all nodes whose unbond period is over, see `getNodeUnbondEta`,
+    /// i.e. what would return from the auction contracts if all of them were unbonded now.
+    /// Uses `getNodeStakedInAuction`, or the stake of the node if none was recorded,
+    /// e.g. for nodes staked before it was tracked.
+    /// Counts nodes from all auction contracts, while `unBondAllPossibleNodes` only unbonds from one at a time.
+    #[view(getTotalUnbondableNow)]
+    fn get_total_unbondable_now(&self) -> Self::BigUint {
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let stake_per_node = self.get_stake_per_node();
+        let bl_nonce = self.blockchain().get_block_nonce();
+        let num_nodes = self.num_nodes().get();
+        let mut total = Self::BigUint::zero();
+        for node_id in 1..=num_nodes {
+            let unbond_eta = self.node_unbond_eta(node_id, n_blocks_before_unbond);
+            if unbond_eta > 0 && unbond_eta <= bl_nonce {
+                let staked_in_auction = self.get_node_staked_in_auction(node_id);
+                if staked_in_auction > 0 {
+                    total += staked_in_auction;
+                } else {
+                    total += self.get_node_stake_or_default(node_id, &stake_per_node);
+                }
+            }
+        }
+        total
+    }
+
+    fn node_unbond_eta(&self, node_id: usize, n_blocks_before_unbond: u64) -> u64 {
+        if let NodeState::UnBondPeriod { started } = self.get_node_state(node_id) {
+            // overflow means the unbond period never ends
+            started.saturating_add(n_blocks_before_unbond)
+        } else {
+            0
+        }
+    }
+
     fn prepare_node_for_unbond_if_possible(&self, node_id: usize) -> bool {
         if let NodeState::UnBondPeriod { started } = self.get_node_state(node_id) {
             self.set_node_state(
@@ -376,13 +1712,14 @@ pub trait NodeActivationModule:
         &self,
         node_ids: Vec<usize>,
         bls_keys: Vec<BLSKey>,
-    ) -> AsyncCall<Self::SendApi> {
+    ) -> SCResult<AsyncCall<Self::SendApi>> {
         // send unbond command to Auction SC
-        let auction_contract_addr = self.get_auction_contract_address();
-        self.auction_proxy(auction_contract_addr)
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
+        Ok(self
+            .auction_proxy(auction_contract_addr)
             .unbond_nodes(bls_keys.into())
             .async_call()
-            .with_callback(self.callbacks().auction_unbond_callback(node_ids))
+            .with_callback(self.callbacks().auction_unbond_callback(node_ids)))
     }
 
     /// Only finalize deactivation if we got confirmation from the auction contract.
@@ -395,11 +1732,15 @@ pub trait NodeActivationModule:
     ) -> SCResult<()> {
         match call_result {
             AsyncCallResult::Ok(node_status_args) => {
-                let (node_ids_ok, node_ids_fail) =
+                let (node_ids_ok, node_ids_already_staked, node_statuses_fail) =
                     self.split_node_ids_by_err(node_ids, node_status_args);
                 self.auction_unbond_callback_ok(node_ids_ok)?;
                 self.auction_unbond_callback_fail(
-                    node_ids_fail,
+                    node_statuses_fail
+                        .iter()
+                        .map(|&(node_id, _)| node_id)
+                        .chain(node_ids_already_staked.into_iter())
+                        .collect(),
                     &b"unbonding failed for some nodes"[..],
                 )?;
                 Ok(())
@@ -410,19 +1751,36 @@ pub trait NodeActivationModule:
         }
     }
 
+    /// No funds are moved here: the unbonded stake is not credited to any delegator,
+    /// deferred payments are created when the stake is unstaked, not when it returns.
+    /// The auction contract sends the unbonded stake back as a separate transfer.
     fn auction_unbond_callback_ok(&self, node_ids: Vec<usize>) -> SCResult<()> {
+        let node_ids = self.filter_nodes_by_state(node_ids, |node_state| {
+            matches!(node_state, NodeState::PendingUnBond { .. })
+        });
         if node_ids.is_empty() {
             return Ok(());
         }
 
         // set nodes to Inactive + reset unstake nonce since it is no longer needed
+        let mut unbonded_stake = Self::BigUint::zero();
         for &node_id in node_ids.iter() {
             self.set_node_state(node_id, NodeState::Inactive);
+            self.record_node_state_change(node_id);
+            unbonded_stake += &self.get_node_staked_in_auction(node_id);
+            self.clear_node_staked_in_auction(node_id);
         }
 
-        // log event (no data)
-        // TODO: log BLS keys of nodes in data
-        self.unbond_node_ok_event(());
+        // log event, with the BLS keys of the affected nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.unbond_node_ok_event(bls_keys.as_slice());
+
+        let staked_in_auction = self.get_staked_in_auction();
+        if staked_in_auction > unbonded_stake {
+            self.update_staked_in_auction(&(staked_in_auction - unbonded_stake));
+        } else {
+            self.update_staked_in_auction(&Self::BigUint::zero());
+        }
 
         Ok(())
     }
@@ -432,31 +1790,47 @@ pub trait NodeActivationModule:
             return Ok(());
         }
 
-        // revert nodes to UnBondPeriod
+        // check all nodes before changing any state
+        let mut unbond_started_per_node = Vec::with_capacity(node_ids.len());
         for &node_id in node_ids.iter() {
             if let NodeState::PendingUnBond { unbond_started } = self.get_node_state(node_id) {
-                self.set_node_state(
-                    node_id,
-                    NodeState::UnBondPeriod {
-                        started: unbond_started,
-                    },
-                );
+                unbond_started_per_node.push(unbond_started);
             } else {
                 return sc_error!("node not pending unbond");
             }
         }
 
-        // log failure event (no data)
-        self.unbond_node_fail_event(err_msg);
+        // revert nodes to UnBondPeriod
+        for (&node_id, &unbond_started) in node_ids.iter().zip(unbond_started_per_node.iter()) {
+            self.set_node_state(
+                node_id,
+                NodeState::UnBondPeriod {
+                    started: unbond_started,
+                },
+            );
+        }
+
+        // log failure event, with the BLS keys of the failed nodes as data
+        let bls_keys = self.get_bls_keys_of_nodes(&node_ids);
+        self.unbond_node_fail_event(err_msg, bls_keys.as_slice());
 
         Ok(())
     }
 
     /// Claims from auction SC funds that were sent but are not required to run the nodes.
+    /// Only claims from the main auction contract.
+    /// Logs the EGLD returned by the auction contract.
+    /// The claimed EGLD stays unprotected: it can be unbonded or rejected delegator stake,
+    /// so none of it is credited to the owner reserve.
     #[endpoint(claimUnusedFunds)]
     fn claim_unused_funds(&self) -> SCResult<AsyncCall<Self::SendApi>> {
         only_owner!(self, "only owner can claim inactive stake from auction");
 
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
@@ -467,7 +1841,74 @@ pub trait NodeActivationModule:
         Ok(self
             .auction_proxy(auction_contract_addr)
             .claim()
-            .async_call())
+            .async_call()
+            .with_callback(self.callbacks().auction_claim_unused_funds_callback()))
+    }
+
+    /// The claimed EGLD comes back with the callback.
+    /// `#[callback]` also has be declared in lib.rs for the moment.
+    #[callback]
+    fn auction_claim_unused_funds_callback(&self, #[call_result] call_result: AsyncCallResult<()>) {
+        if let AsyncCallResult::Ok(()) = call_result {
+            let claimed = self.call_value().egld_value();
+            self.claim_unused_funds_event(&claimed);
+        }
+    }
+
+    /// Claims back from the auction contract the stake of the nodes it rejected.
+    /// Only nodes belonging to the same auction contract as the first failed node are handled.
+    /// The nodes become inactive once the funds are back, and can be staked again.
+    #[endpoint(claimFailedStake)]
+    fn claim_failed_stake(&self) -> SCResult<AsyncCall<Self::SendApi>> {
+        only_owner!(self, "only owner can claim failed stake");
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let mut auction_index = None;
+        let mut node_ids = Vec::<usize>::new();
+        let num_nodes = self.num_nodes().get();
+        for node_id in 1..=num_nodes {
+            if self.get_node_state(node_id) == NodeState::ActivationFailed
+                && self.is_node_in_auction(node_id, &mut auction_index)
+            {
+                node_ids.push(node_id);
+            }
+        }
+
+        require!(!node_ids.is_empty(), "no failed stake to claim");
+
+        // send claim command to Auction SC
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
+        Ok(self
+            .auction_proxy(auction_contract_addr)
+            .claim()
+            .async_call()
+            .with_callback(self.callbacks().auction_claim_callback(node_ids)))
+    }
+
+    /// Nodes only become inactive if the funds were successfully claimed.
+    /// The claimed funds add to the contract balance, and are therefore unprotected again.
+    /// `#[callback]` also has be declared in lib.rs for the moment.
+    #[callback]
+    fn auction_claim_callback(
+        &self,
+        node_ids: Vec<usize>,
+        #[call_result] call_result: AsyncCallResult<()>,
+    ) {
+        if let AsyncCallResult::Ok(()) = call_result {
+            for &node_id in node_ids.iter() {
+                self.set_node_state(node_id, NodeState::Inactive);
+                self.record_node_state_change(node_id);
+            }
+        }
     }
 
     #[payable("EGLD")]
@@ -477,9 +1918,18 @@ pub trait NodeActivationModule:
         #[var_args] bls_keys: VarArgs<BLSKey>,
         #[payment] fine_payment: Self::BigUint,
     ) -> SCResult<AsyncCall<Self::SendApi>> {
-        only_owner!(self, "only owner allowed to unjail nodes");
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unjail nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
 
         // validation only
+        let mut node_ids = Vec::<usize>::with_capacity(bls_keys.len());
         for bls_key in bls_keys.iter() {
             let node_id = self.get_node_id(bls_key);
             require!(node_id != 0, "unknown node provided");
@@ -487,14 +1937,176 @@ pub trait NodeActivationModule:
                 self.get_node_state(node_id) == NodeState::Active,
                 "node must be active"
             );
+            node_ids.push(node_id);
         }
 
         // send unJail command to Auction SC
-        let auction_contract_addr = self.get_auction_contract_address();
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
         Ok(self
             .auction_proxy(auction_contract_addr)
             .with_token_transfer(TokenIdentifier::egld(), fine_payment)
             .unjail(bls_keys)
             .async_call())
     }
+
+    /// Same as `unJailNodes`, but nodes that are not active are skipped instead of failing the whole transaction.
+    /// The fine is split evenly between all provided nodes,
+    /// the part corresponding to the skipped nodes is returned to the caller.
+    /// Only nodes belonging to the same auction contract as the first active node are unjailed.
+    /// Returns the skipped BLS keys, followed by the async call.
+    #[payable("EGLD")]
+    #[endpoint(unJailPossibleNodes)]
+    fn unjail_possible_nodes(
+        &self,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+        #[payment] fine_payment: Self::BigUint,
+    ) -> SCResult<MultiResult2<MultiResultVec<BLSKey>, AsyncCall<Self::SendApi>>> {
+        require!(
+            self.is_owner_or_operator(&self.blockchain().get_caller()),
+            "only owner or operator allowed to unjail nodes"
+        );
+
+        require!(
+            !self.is_emergency_paused(),
+            "node operations are paused for emergency"
+        );
+
+        require!(!bls_keys.is_empty(), "no BLS keys provided");
+
+        let num_keys = Self::BigUint::from(bls_keys.len());
+        require!(
+            &fine_payment % &num_keys == 0,
+            "fine must be evenly split between nodes"
+        );
+        let fine_per_node = &fine_payment / &num_keys;
+
+        let mut auction_index = None;
+        let mut node_ids = Vec::<usize>::new();
+        let mut unjail_bls_keys = Vec::<BLSKey>::new();
+        let mut skipped_bls_keys = Vec::<BLSKey>::new();
+        for bls_key in bls_keys.into_vec().into_iter() {
+            let node_id = self.get_node_id(&bls_key);
+            if node_id != 0
+                && self.get_node_state(node_id) == NodeState::Active
+                && self.is_node_in_auction(node_id, &mut auction_index)
+            {
+                node_ids.push(node_id);
+                unjail_bls_keys.push(bls_key);
+            } else {
+                skipped_bls_keys.push(bls_key);
+            }
+        }
+
+        require!(!node_ids.is_empty(), "no nodes can be unjailed");
+
+        let fine_to_send = &fine_per_node * &Self::BigUint::from(node_ids.len());
+        let fine_to_return = &fine_payment - &fine_to_send;
+        if fine_to_return > 0 {
+            let caller = self.blockchain().get_caller();
+            self.send()
+                .direct_egld(&caller, &fine_to_return, b"unjail fine for skipped nodes");
+        }
+
+        // send unJail command to Auction SC
+        let auction_contract_addr = self.get_auction_contract_address_of_nodes(&node_ids)?;
+        let async_call = self
+            .auction_proxy(auction_contract_addr)
+            .with_token_transfer(TokenIdentifier::egld(), fine_to_send)
+            .unjail(unjail_bls_keys.into())
+            .async_call();
+
+        Ok((skipped_bls_keys.into(), async_call).into())
+    }
+
+    // MULTIPLE AUCTION CONTRACTS
+
+    /// Points the contract to a redeployed main auction contract.
+    /// Only allowed while none of the nodes of the main auction contract are staked there,
+    /// or waiting for a callback from it, since those calls would go to the new address.
+    #[endpoint(setAuctionContractAddress)]
+    fn set_auction_contract_address(&self, auction_addr: Address) -> SCResult<()> {
+        only_owner!(self, "only owner can set the auction contract address");
+
+        require!(
+            !auction_addr.is_zero(),
+            "auction contract address cannot be zero"
+        );
+
+        for node_id in 1..=self.num_nodes().get() {
+            if self.get_node_auction_index(node_id) != 0 {
+                continue;
+            }
+            require!(
+                matches!(
+                    self.get_node_state(node_id),
+                    NodeState::Inactive | NodeState::Removed
+                ),
+                "all nodes of the auction contract must be inactive"
+            );
+        }
+
+        let old_auction_addr = self.get_auction_contract_address();
+        self.set_auction_addr(&auction_addr);
+        self.auction_contract_address_changed_event(&old_auction_addr, &auction_addr);
+
+        Ok(())
+    }
+
+    /// Assigns nodes to one of the registered auction contracts, 0 being the main one.
+    /// Nodes can only be moved while they are not staked.
+    #[endpoint(setNodesAuctionIndex)]
+    fn set_nodes_auction_index(
+        &self,
+        auction_index: usize,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner allowed to set node auction contract");
+
+        require!(
+            auction_index < self.get_num_auction_contracts(),
+            "unknown auction contract"
+        );
+
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(bls_key);
+            require!(node_id != 0, "unknown node provided");
+            require!(
+                matches!(
+                    self.get_node_state(node_id),
+                    NodeState::Inactive | NodeState::Removed
+                ),
+                "node must be inactive"
+            );
+            self.set_node_auction_index(node_id, auction_index);
+        }
+
+        Ok(())
+    }
+
+    /// Only one async call can be performed per transaction,
+    /// so all nodes in an operation must belong to the same auction contract.
+    fn get_auction_contract_address_of_nodes(&self, node_ids: &[usize]) -> SCResult<Address> {
+        let mut auction_index = None;
+        for &node_id in node_ids.iter() {
+            require!(
+                self.is_node_in_auction(node_id, &mut auction_index),
+                "nodes belong to different auction contracts"
+            );
+        }
+
+        Ok(self.get_auction_contract_address_by_index(auction_index.unwrap_or(0)))
+    }
+
+    /// Used when scanning nodes: the first node checked fixes the auction contract,
+    /// following nodes only match if they belong to the same one.
+    fn is_node_in_auction(&self, node_id: usize, auction_index: &mut Option<usize>) -> bool {
+        let node_auction_index = self.get_node_auction_index(node_id);
+        match *auction_index {
+            Some(index) => index == node_auction_index,
+            None => {
+                *auction_index = Some(node_auction_index);
+                true
+            }
+        }
+    }
 }
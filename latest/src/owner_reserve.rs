@@ -0,0 +1,119 @@
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
+
+elrond_wasm::imports!();
+
+/// The owner can keep a reserve of EGLD in the contract, as a buffer for node stake and fees.
+/// The reserve is not delegated stake: it has no fund type and earns no rewards.
+/// It is part of the unprotected funds, so it can be used to stake nodes like any unprotected funds.
+#[elrond_wasm_derive::module]
+pub trait OwnerReserveModule:
+    crate::settings::SettingsModule
+    + crate::rewards_state::RewardStateModule
+    + node_storage::node_config::NodeConfigModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+{
+    #[view(getOwnerReserve)]
+    #[storage_get("owner_reserve")]
+    fn get_owner_reserve(&self) -> Self::BigUint;
+
+    #[storage_set("owner_reserve")]
+    fn set_owner_reserve(&self, owner_reserve: &Self::BigUint);
+
+    #[payable("EGLD")]
+    #[endpoint(addOwnerReserve)]
+    fn add_owner_reserve(&self, #[payment] payment: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can add to the owner reserve");
+        require!(payment > 0, "cannot add zero to the owner reserve");
+
+        let mut owner_reserve = self.get_owner_reserve();
+        owner_reserve += &payment;
+        self.set_owner_reserve(&owner_reserve);
+
+        Ok(())
+    }
+
+    /// Only works if there are enough unprotected funds in the contract that do not back delegated stake,
+    /// see `get_unprotected_not_delegated`, i.e. not if the reserve is currently staked with nodes.
+    #[endpoint(withdrawOwnerReserve)]
+    fn withdraw_owner_reserve(&self, amount: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can withdraw the owner reserve");
+        require!(amount > 0, "cannot withdraw zero from the owner reserve");
+
+        let mut owner_reserve = self.get_owner_reserve();
+        require!(
+            amount <= owner_reserve,
+            "cannot withdraw more than the owner reserve"
+        );
+        require!(
+            amount <= self.get_unprotected_not_delegated(),
+            "not enough funds in contract to withdraw the owner reserve"
+        );
+
+        owner_reserve -= &amount;
+        self.set_owner_reserve(&owner_reserve);
+
+        let caller = self.blockchain().get_caller();
+        self.send()
+            .direct_egld(&caller, &amount, b"owner reserve withdraw");
+
+        Ok(())
+    }
+
+    /// Unprotected funds minus the delegated stake (Active + UnStaked) not held by the auction contract.
+    /// The reserve is not tracked separately once used to stake nodes,
+    /// so this is what the owner can take out without touching delegated stake.
+    fn get_unprotected_not_delegated(&self) -> Self::BigUint {
+        let unprotected = self.total_unprotected();
+
+        let mut delegated = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+        delegated += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+        let staked_in_auction = self.get_staked_in_auction();
+        if delegated > staked_in_auction {
+            delegated -= staked_in_auction;
+        } else {
+            delegated = Self::BigUint::zero();
+        }
+
+        if unprotected > delegated {
+            unprotected - delegated
+        } else {
+            Self::BigUint::zero()
+        }
+    }
+
+    /// EGLD that is neither delegated stake, nor rewards, nor the owner reserve,
+    /// e.g. sent to the contract by mistake.
+    /// Delegated stake counts as covered only up to `getStakedInAuction`, and the result
+    /// never exceeds the unprotected funds, so stake held by the auction contract is never swept.
+    /// Stake sent to the auction contract but not yet confirmed, or unstaked tokens on their way back,
+    /// are not counted as staked, so this can be lower than the actual surplus.
+    #[view(getUnaccountedFunds)]
+    fn get_unaccounted_funds(&self) -> Self::BigUint {
+        // the unprotected funds already exclude rewards, waiting stake, deferred payments and withdraw-only funds
+        let available = self.get_unprotected_not_delegated();
+        let owner_reserve = self.get_owner_reserve();
+        if available > owner_reserve {
+            available - owner_reserve
+        } else {
+            Self::BigUint::zero()
+        }
+    }
+
+    /// Sends all unaccounted funds to the owner. Yields the amount sent.
+    #[endpoint(sweepUnaccountedFunds)]
+    fn sweep_unaccounted_funds(&self) -> SCResult<Self::BigUint> {
+        only_owner!(self, "only owner can sweep unaccounted funds");
+
+        let unaccounted = self.get_unaccounted_funds();
+        require!(unaccounted > 0, "no unaccounted funds");
+
+        let caller = self.blockchain().get_caller();
+        self.send()
+            .direct_egld(&caller, &unaccounted, b"unaccounted funds sweep");
+
+        Ok(unaccounted)
+    }
+}
@@ -1,10 +1,11 @@
 use crate::reset_checkpoint_types::{
     ComputeAllRewardsData, GlobalOpCheckpoint, ModifyDelegationCapStep,
-    ModifyTotalDelegationCapData,
+    ModifyTotalDelegationCapData, RewardCheckpointSnapshot,
 };
 use crate::settings::{OWNER_USER_ID, PERCENTAGE_DENOMINATOR};
 use core::cmp::Ordering;
 use elrond_wasm_module_features::feature_guard;
+use node_storage::types::NodeState;
 use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
 use user_fund_storage::types::FundType;
 
@@ -37,6 +38,12 @@ pub trait ResetCheckpointsModule:
         &self,
         mut orc: Box<GlobalOpCheckpoint<Self::BigUint>>,
     ) -> SCResult<OperationCompletionStatus> {
+        let was_in_progress = !orc.is_none();
+        require!(
+            !was_in_progress || !self.are_rewards_frozen(),
+            "rewards are frozen"
+        );
+
         let mut status = OperationCompletionStatus::Completed;
         while matches!(status, OperationCompletionStatus::Completed) && !orc.is_none() {
             let (new_status, new_orc) = self.continue_global_operation_step(orc);
@@ -45,6 +52,15 @@ pub trait ResetCheckpointsModule:
         }
 
         self.global_op_checkpoint().set(&orc);
+
+        if was_in_progress && orc.is_none() {
+            self.push_reward_checkpoint_snapshot(&RewardCheckpointSnapshot {
+                block_nonce: self.blockchain().get_block_nonce(),
+                total_cumulated_rewards: self.get_total_cumulated_rewards(),
+                total_rewardable_stake: self.get_total_rewardable_stake(),
+            });
+        }
+
         Ok(status)
     }
 
@@ -170,12 +186,15 @@ pub trait ResetCheckpointsModule:
     /// This process might be longer then one block - reaching the gaslimit
     /// thus will do it by saving where it left before reaching out of gas.
     /// No change in the delegators total cap is allowed before all the checkpoints are recalculated.
+    /// Only used by global operations (delegation cap and service fee changes), not by node operations.
     ///
     /// Returns something if there is more computing to be done.
     fn compute_all_rewards(
         &self,
         mut data: ComputeAllRewardsData<Self::BigUint>,
     ) -> Option<ComputeAllRewardsData<Self::BigUint>> {
+        self.log_rewards_received();
+
         // if more rewards arrived since computation started,
         // it must be restarted from scratch
         let curr_rewards_checkpoint = self.get_total_cumulated_rewards();
@@ -212,6 +231,43 @@ pub trait ResetCheckpointsModule:
         None
     }
 
+    /// Leaves bootstrap mode without waiting for the total delegation cap to be filled.
+    /// The total delegation cap is lowered to the stake currently in the contract.
+    /// Bootstrap mode can only be ended once, there is no way back into it.
+    #[endpoint(endBootstrapMode)]
+    fn end_bootstrap_mode(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can end bootstrap mode");
+
+        require!(self.is_bootstrap_mode(), "not in bootstrap mode");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "cannot end bootstrap mode when a global operation is in progress"
+        );
+
+        require!(
+            !self.get_auction_contract_address().is_zero(),
+            "auction contract address not set"
+        );
+
+        require!(self.get_stake_per_node() > 0, "stake per node not set");
+
+        let has_nodes = (1..=self.num_nodes().get())
+            .any(|node_id| self.get_node_state(node_id) != NodeState::Removed);
+        require!(has_nodes, "no nodes registered");
+
+        // in bootstrap mode waiting stake is activated and unstaked stake is released immediately,
+        // so all the stake in the contract is active
+        let total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+
+        // no rewards to compute, no nodes could have been staked in bootstrap mode
+        self.set_bootstrap_mode(false);
+        self.set_total_delegation_cap(total_active.clone());
+        self.bootstrap_mode_ended_event(&total_active);
+
+        Ok(())
+    }
+
     /// Total delegation cap can be modified by owner only.
     /// It will recalculate and set the checkpoint for all the delegators
     #[endpoint(modifyTotalDelegationCap)]
@@ -325,8 +381,9 @@ pub trait ResetCheckpointsModule:
         self.continue_global_operation(orc)
     }
 
-    /// The stake per node can be changed by the owner.
-    /// It does not get set in the contructor, so the owner has to manually set it after the contract is deployed.
+    /// The service fee can be changed by the owner.
+    /// Rewards accumulated so far are first computed with the old fee, for all delegators.
+    /// The new fee only applies once this global operation completes.
     #[endpoint(setServiceFee)]
     fn set_service_fee_endpoint(
         &self,
@@ -1,4 +1,7 @@
-use crate::reset_checkpoint_types::GlobalOpCheckpoint;
+use crate::reset_checkpoint_types::{GlobalOpCheckpoint, RewardCheckpointSnapshot};
+
+/// Only the most recent reward checkpoint snapshots are kept.
+pub const REWARD_CHECKPOINT_HISTORY_MAX_LEN: usize = 32;
 
 elrond_wasm::imports!();
 
@@ -14,4 +17,96 @@ pub trait ResetCheckpointStateModule {
     fn is_global_op_in_progress(&self) -> bool {
         !self.global_op_checkpoint().is_empty()
     }
+
+    /// A new service fee only applies after all rewards were computed with the old one.
+    /// Yields the new service fee while this computation is in progress.
+    #[view(getPendingServiceFee)]
+    fn get_pending_service_fee(&self) -> OptionalResult<Self::BigUint> {
+        if let GlobalOpCheckpoint::ChangeServiceFee {
+            new_service_fee, ..
+        } = *self.global_op_checkpoint().get()
+        {
+            OptionalResult::Some(new_service_fee)
+        } else {
+            OptionalResult::None
+        }
+    }
+
+    /// Snapshots are stored in a ring buffer of `REWARD_CHECKPOINT_HISTORY_MAX_LEN` slots.
+    #[storage_get("reward_checkp_snapshot")]
+    fn get_reward_checkpoint_snapshot(
+        &self,
+        slot: usize,
+    ) -> RewardCheckpointSnapshot<Self::BigUint>;
+
+    #[storage_set("reward_checkp_snapshot")]
+    fn set_reward_checkpoint_snapshot(
+        &self,
+        slot: usize,
+        snapshot: &RewardCheckpointSnapshot<Self::BigUint>,
+    );
+
+    /// Total number of snapshots ever taken, including the evicted ones.
+    #[storage_get("num_reward_checkp_snapshots")]
+    fn get_num_reward_checkpoint_snapshots(&self) -> usize;
+
+    #[storage_set("num_reward_checkp_snapshots")]
+    fn set_num_reward_checkpoint_snapshots(&self, num_snapshots: usize);
+
+    /// Saves a snapshot, overwriting the oldest one if the history is full.
+    fn push_reward_checkpoint_snapshot(&self, snapshot: &RewardCheckpointSnapshot<Self::BigUint>) {
+        let num_snapshots = self.get_num_reward_checkpoint_snapshots();
+        self.set_reward_checkpoint_snapshot(
+            num_snapshots % REWARD_CHECKPOINT_HISTORY_MAX_LEN,
+            snapshot,
+        );
+        self.set_num_reward_checkpoint_snapshots(num_snapshots + 1);
+    }
+
+    fn get_last_reward_checkpoint_snapshot(
+        &self,
+    ) -> Option<RewardCheckpointSnapshot<Self::BigUint>> {
+        let num_snapshots = self.get_num_reward_checkpoint_snapshots();
+        if num_snapshots == 0 {
+            None
+        } else {
+            Some(self.get_reward_checkpoint_snapshot(
+                (num_snapshots - 1) % REWARD_CHECKPOINT_HISTORY_MAX_LEN,
+            ))
+        }
+    }
+
+    /// Number of reward checkpoint snapshots that can be queried with `getRewardCheckpointAt`.
+    #[view(getRewardCheckpointCount)]
+    fn get_reward_checkpoint_count(&self) -> usize {
+        core::cmp::min(
+            self.get_num_reward_checkpoint_snapshots(),
+            REWARD_CHECKPOINT_HISTORY_MAX_LEN,
+        )
+    }
+
+    /// Block nonce, total cumulated rewards and total rewardable stake when a global operation completed.
+    /// Index 0 is the oldest snapshot still kept. Yields nothing for indexes past `getRewardCheckpointCount`.
+    #[view(getRewardCheckpointAt)]
+    fn get_reward_checkpoint_at(
+        &self,
+        index: usize,
+    ) -> OptionalResult<MultiResult3<u64, Self::BigUint, Self::BigUint>> {
+        let count = self.get_reward_checkpoint_count();
+        if index >= count {
+            return OptionalResult::None;
+        }
+
+        let first = self.get_num_reward_checkpoint_snapshots() - count;
+        let snapshot = self
+            .get_reward_checkpoint_snapshot((first + index) % REWARD_CHECKPOINT_HISTORY_MAX_LEN);
+        OptionalResult::Some(
+            (
+                snapshot.block_nonce,
+                snapshot.total_cumulated_rewards,
+                snapshot.total_rewardable_stake,
+            )
+                .into(),
+        )
+    }
 }
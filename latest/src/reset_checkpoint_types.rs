@@ -66,6 +66,15 @@ impl<BigUint: BigUintApi> ComputeAllRewardsData<BigUint> {
     }
 }
 
+/// Rewards and rewardable stake at the moment a global operation completed.
+/// The reward rate between 2 snapshots is the difference in cumulated rewards divided by the rewardable stake.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, PartialEq, Debug)]
+pub struct RewardCheckpointSnapshot<BigUint: BigUintApi> {
+    pub block_nonce: u64,
+    pub total_cumulated_rewards: BigUint,
+    pub total_rewardable_stake: BigUint,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
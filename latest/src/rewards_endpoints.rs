@@ -1,9 +1,16 @@
 use elrond_wasm_module_features::feature_guard;
 
+use crate::settings::PERCENTAGE_DENOMINATOR;
 use core::num::NonZeroUsize;
 
 elrond_wasm::imports!();
 
+/// Assuming 6 second blocks.
+pub const BLOCKS_PER_YEAR: u64 = 5_256_000;
+
+/// Gas reserved for claiming and sending the rewards of one more delegator in `claimRewardsForUsers`.
+pub const CLAIM_REWARDS_GASLIMIT: u64 = 10_000_000;
+
 #[elrond_wasm_derive::module]
 pub trait RewardEndpointsModule:
     crate::settings::SettingsModule
@@ -27,6 +34,7 @@ pub trait RewardEndpointsModule:
 
         let caller = self.blockchain().get_caller();
         let user_id = non_zero_usize!(self.get_user_id(&caller), "unknown caller");
+        self.record_user_activity(user_id.get());
 
         require!(
             !self.is_global_op_in_progress(),
@@ -36,9 +44,10 @@ pub trait RewardEndpointsModule:
         let mut user_data = self.load_updated_user_rewards(user_id);
 
         if user_data.unclaimed_rewards > 0 {
-            self.claim_rewards_event(&caller, &user_data.unclaimed_rewards);
+            let reward_address = self.get_reward_address_of_user(user_id.get(), &caller);
+            self.claim_rewards_event(&caller, &reward_address, &user_data.unclaimed_rewards);
 
-            self.send_rewards(&caller, &user_data.unclaimed_rewards);
+            self.send_rewards(&reward_address, &user_data.unclaimed_rewards);
 
             user_data.unclaimed_rewards = Self::BigUint::zero();
         }
@@ -48,6 +57,139 @@ pub trait RewardEndpointsModule:
         Ok(())
     }
 
+    /// Same as `claimRewards`, but only sends the given amount.
+    /// The rest of the rewards remain unclaimed.
+    #[endpoint(claimRewardsAmount)]
+    fn claim_rewards_amount(&self, amount: Self::BigUint) -> SCResult<()> {
+        require!(self.not_paused(), "contract paused");
+        feature_guard!(self, b"claimRewards", true);
+
+        let caller = self.blockchain().get_caller();
+        let user_id = non_zero_usize!(self.get_user_id(&caller), "unknown caller");
+        self.record_user_activity(user_id.get());
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "claim rewards is temporarily paused as checkpoint is reset"
+        );
+
+        require!(amount > 0, "cannot claim zero rewards");
+
+        let mut user_data = self.load_updated_user_rewards(user_id);
+        require!(
+            amount <= user_data.unclaimed_rewards,
+            "cannot claim more than the unclaimed rewards"
+        );
+
+        let reward_address = self.get_reward_address_of_user(user_id.get(), &caller);
+        self.claim_rewards_event(&caller, &reward_address, &amount);
+
+        self.send_rewards(&reward_address, &amount);
+
+        user_data.unclaimed_rewards -= &amount;
+
+        self.store_user_reward_data(user_id, &user_data);
+
+        Ok(())
+    }
+
+    /// Claims and sends the rewards of several delegators at once, e.g. for scheduled payouts.
+    /// Can be called by anyone, since the rewards of each delegator
+    /// always go to their own address, or the reward address they configured.
+    /// Unknown addresses and delegators with no rewards are skipped.
+    /// Stops early if running out of gas, so batches of up to 50 addresses are recommended.
+    /// Yields the number of addresses processed, the rest can be sent in another transaction.
+    #[endpoint(claimRewardsForUsers)]
+    fn claim_rewards_for_users(
+        &self,
+        #[var_args] user_addresses: VarArgs<Address>,
+    ) -> SCResult<usize> {
+        require!(self.not_paused(), "contract paused");
+        feature_guard!(self, b"claimRewards", true);
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "claim rewards is temporarily paused as checkpoint is reset"
+        );
+
+        let mut num_processed = 0;
+        for user_address in user_addresses.iter() {
+            if self.blockchain().get_gas_left() < CLAIM_REWARDS_GASLIMIT {
+                break;
+            }
+            num_processed += 1;
+
+            let user_id = match NonZeroUsize::new(self.get_user_id(user_address)) {
+                Some(user_id) => user_id,
+                None => continue,
+            };
+
+            let mut user_data = self.load_updated_user_rewards(user_id);
+            if user_data.unclaimed_rewards == 0 {
+                continue;
+            }
+
+            let reward_address = self.get_reward_address_of_user(user_id.get(), user_address);
+            self.claim_rewards_event(user_address, &reward_address, &user_data.unclaimed_rewards);
+
+            self.send_rewards(&reward_address, &user_data.unclaimed_rewards);
+
+            user_data.unclaimed_rewards = Self::BigUint::zero();
+            self.store_user_reward_data(user_id, &user_data);
+        }
+
+        Ok(num_processed)
+    }
+
+    /// Delegators can have their rewards sent to another address than the one they stake from.
+    /// Setting the reward address to the caller's own address is the same as clearing it.
+    /// The zero address and the delegation contract itself are rejected.
+    #[endpoint(setRewardAddress)]
+    fn set_reward_address(&self, reward_address: Address) -> SCResult<()> {
+        let caller = self.blockchain().get_caller();
+        let user_id = self.get_user_id(&caller);
+        require!(user_id > 0, "unknown caller");
+
+        require!(!reward_address.is_zero(), "reward address cannot be zero");
+        require!(
+            reward_address != self.blockchain().get_sc_address(),
+            "reward address cannot be the delegation contract"
+        );
+
+        if reward_address == caller {
+            self.clear_user_reward_address(user_id);
+        } else {
+            self.set_user_reward_address(user_id, &reward_address);
+        }
+        Ok(())
+    }
+
+    /// Rewards go back to being sent to the caller's own address.
+    #[endpoint(clearRewardAddress)]
+    fn clear_reward_address(&self) -> SCResult<()> {
+        let caller = self.blockchain().get_caller();
+        let user_id = self.get_user_id(&caller);
+        require!(user_id > 0, "unknown caller");
+
+        self.clear_user_reward_address(user_id);
+        Ok(())
+    }
+
+    /// Yields the address where the rewards of a delegator are sent.
+    #[view(getRewardAddress)]
+    fn get_reward_address(&self, user_address: Address) -> Address {
+        let user_id = self.get_user_id(&user_address);
+        self.get_reward_address_of_user(user_id, &user_address)
+    }
+
+    fn get_reward_address_of_user(&self, user_id: usize, user_address: &Address) -> Address {
+        if user_id == 0 || self.is_empty_user_reward_address(user_id) {
+            user_address.clone()
+        } else {
+            self.get_user_reward_address(user_id)
+        }
+    }
+
     fn send_rewards(&self, to: &Address, amount: &Self::BigUint) {
         // send funds
         self.send()
@@ -58,4 +200,33 @@ pub trait RewardEndpointsModule:
         sent_rewards += amount;
         self.set_sent_rewards(&sent_rewards);
     }
+
+    /// Annual delegator yield on active stake, in basis points, after the service fee.
+    /// Extrapolated from the rewards received since the last reward checkpoint snapshot,
+    /// i.e. since the last global operation completed, see `getRewardCheckpointAt`.
+    /// Zero if there is no snapshot yet or no block passed since.
+    #[view(getEstimatedApr)]
+    fn get_estimated_apr(&self) -> Self::BigUint {
+        let snapshot = match self.get_last_reward_checkpoint_snapshot() {
+            Some(snapshot) => snapshot,
+            None => return Self::BigUint::zero(),
+        };
+
+        let n_blocks = self
+            .blockchain()
+            .get_block_nonce()
+            .saturating_sub(snapshot.block_nonce);
+        let total_rewardable_stake = self.get_total_rewardable_stake();
+        if n_blocks == 0 || total_rewardable_stake == 0 {
+            return Self::BigUint::zero();
+        }
+
+        // basis points and the service fee share the same denominator, so they cancel out
+        let mut apr = self.get_total_cumulated_rewards() - snapshot.total_cumulated_rewards;
+        apr *= &Self::BigUint::from(BLOCKS_PER_YEAR);
+        apr *= &(Self::BigUint::from(PERCENTAGE_DENOMINATOR) - self.get_service_fee());
+        apr /= &Self::BigUint::from(n_blocks);
+        apr /= &total_rewardable_stake;
+        apr
+    }
 }
@@ -19,6 +19,7 @@ pub struct UserRewardData<BigUint> {
 #[elrond_wasm_derive::module]
 pub trait RewardStateModule:
     crate::settings::SettingsModule
+    + node_storage::node_config::NodeConfigModule
     + user_fund_storage::user_data::UserDataModule
     + user_fund_storage::fund_module::FundModule
     + user_fund_storage::fund_view_module::FundViewModule
@@ -66,6 +67,35 @@ pub trait RewardStateModule:
         self.blockchain().get_cumulated_validator_rewards()
     }
 
+    /// Total cumulated rewards when incoming rewards were last logged, see `log_rewards_received`.
+    #[storage_get("rewards_logged")]
+    fn get_rewards_logged(&self) -> Self::BigUint;
+
+    #[storage_set("rewards_logged")]
+    fn set_rewards_logged(&self, rewards_logged: &Self::BigUint);
+
+    /// Validator rewards arrive without a call to the contract, so they cannot be logged as they come.
+    /// Instead, the rewards that arrived since the last time are logged here.
+    fn log_rewards_received(&self) {
+        let total_cumulated_rewards = self.get_total_cumulated_rewards();
+        let rewards_logged = self.get_rewards_logged();
+        if total_cumulated_rewards > rewards_logged {
+            self.rewards_received_event(
+                &(&total_cumulated_rewards - &rewards_logged),
+                self.blockchain().get_block_nonce(),
+            );
+            self.set_rewards_logged(&total_cumulated_rewards);
+        }
+    }
+
+    /// The stake that rewards are split by.
+    /// This is the total delegation cap, not the active stake:
+    /// rewards for the part of the cap that is not active go to the owner.
+    #[view(getTotalRewardableStake)]
+    fn get_total_rewardable_stake(&self) -> Self::BigUint {
+        self.get_total_delegation_cap()
+    }
+
     /// The account running the nodes is entitled to (service_fee / NODE_DENOMINATOR) * rewards.
     /// Yields the service reward and the non-service-reward.
     ///
@@ -73,6 +103,9 @@ pub trait RewardStateModule:
     /// Both results are rounded down,
     /// so te rounding error is not in the result.
     /// This is deliberate, to avoid a very subtle rounding error edge case.
+    /// The rounding error is never handed out: it stays in the contract and is not counted as unprotected funds.
+    ///
+    /// The service reward is added to the owner's unclaimed rewards, the owner claims it with `claimRewards`.
     fn split_service_reward(&self, tot_rewards: &Self::BigUint) -> (Self::BigUint, Self::BigUint) {
         let service_fee = &self.get_service_fee();
         let perc_denominator = &Self::BigUint::from(PERCENTAGE_DENOMINATOR);
@@ -90,6 +123,9 @@ pub trait RewardStateModule:
     }
 
     /// Does not update storage, only returns the user rewards object, after computing rewards.
+    /// Rewards are computed lazily: only the users whose active stake changes need to be brought up to date,
+    /// so node operations never iterate over all delegators.
+    /// All users only need to be updated when the divisor (total delegation cap) or the service fee change.
     fn load_updated_user_rewards(&self, user_id: NonZeroUsize) -> UserRewardData<Self::BigUint> {
         let mut user_data = self.load_user_reward_data(user_id);
 
@@ -100,17 +136,33 @@ pub trait RewardStateModule:
             return user_data; // nothing happened since the last claim
         }
 
+        user_data.unclaimed_rewards += self.compute_user_new_rewards(user_id, &tot_new_rewards);
+
+        // update user data checkpoint
+        user_data.reward_checkpoint = tot_cumul_rewards;
+
+        user_data
+    }
+
+    /// The part of `tot_new_rewards` that a user is entitled to, under the current stake and service fee.
+    fn compute_user_new_rewards(
+        &self,
+        user_id: NonZeroUsize,
+        tot_new_rewards: &Self::BigUint,
+    ) -> Self::BigUint {
+        let mut user_new_rewards = Self::BigUint::zero();
+
         // the owner is entitled to: tot_new_rewards * service_fee / NODE_DENOMINATOR
         // delegators are entitled to: tot_new_rewards * (1 - service_fee / NODE_DENOMINATOR)
         let (service_rewards, total_delegators_rewards) =
-            self.split_service_reward(&tot_new_rewards);
+            self.split_service_reward(tot_new_rewards);
 
-        let delegation_cap = self.get_total_delegation_cap();
+        let delegation_cap = self.get_total_rewardable_stake();
 
         // update node rewards, if applicable
         if user_id == OWNER_USER_ID {
             // the owner gets the service fee
-            user_data.unclaimed_rewards += &service_rewards;
+            user_new_rewards += &service_rewards;
 
             // the owner gets the rewards for the missing active (unstaked) stake
             let tot_stake_active =
@@ -120,7 +172,7 @@ pub trait RewardStateModule:
                 let mut owner_unstaked_rewards = total_delegators_rewards.clone();
                 owner_unstaked_rewards *= &missing_stake;
                 owner_unstaked_rewards /= &delegation_cap;
-                user_data.unclaimed_rewards += &owner_unstaked_rewards;
+                user_new_rewards += &owner_unstaked_rewards;
             }
         }
 
@@ -132,13 +184,10 @@ pub trait RewardStateModule:
             let mut delegator_new_rewards = total_delegators_rewards;
             delegator_new_rewards *= &u_stake_active;
             delegator_new_rewards /= &delegation_cap;
-            user_data.unclaimed_rewards += &delegator_new_rewards;
+            user_new_rewards += &delegator_new_rewards;
         }
 
-        // update user data checkpoint
-        user_data.reward_checkpoint = tot_cumul_rewards;
-
-        user_data
+        user_new_rewards
     }
 
     /// Convenience method, brings user rewards up to date for one user.
@@ -148,6 +197,8 @@ pub trait RewardStateModule:
     }
 
     /// Yields how much a user is able to claim in rewards at the present time.
+    /// Rewards that arrived since the user's last checkpoint are included,
+    /// so the result is exactly what `claimRewards` would send now.
     /// Does not update storage.
     #[view(getClaimableRewards)]
     fn get_claimable_rewards(&self, user: Address) -> Self::BigUint {
@@ -159,6 +210,23 @@ pub trait RewardStateModule:
         }
     }
 
+    /// How much of a hypothetical `reward_amount` would go to the user,
+    /// if it arrived now, under the current stake and service fee.
+    /// Only the share of the new amount is yielded, rewards already claimable are not included.
+    /// Does not update storage.
+    #[view(simulateRewardDistribution)]
+    fn simulate_reward_distribution(
+        &self,
+        reward_amount: Self::BigUint,
+        user: Address,
+    ) -> Self::BigUint {
+        if let Some(user_id) = NonZeroUsize::new(self.get_user_id(&user)) {
+            self.compute_user_new_rewards(user_id, &reward_amount)
+        } else {
+            Self::BigUint::zero()
+        }
+    }
+
     /// Utility readonly function to check how many unclaimed rewards currently reside in the contract.
     #[view(getTotalUnclaimedRewards)]
     fn get_total_unclaimed_rewards(&self) -> Self::BigUint {
@@ -190,6 +258,54 @@ pub trait RewardStateModule:
         self.set_user_rew_unclaimed(user_id, &data.unclaimed_rewards);
     }
 
+    /// Cumulated rewards per rewarded node, since the contract was created.
+    /// Increases every time it is updated by the new rewards, divided by the number of rewarded nodes.
+    #[storage_get("node_rew_index")]
+    fn get_node_reward_index(&self) -> Self::BigUint;
+
+    #[storage_set("node_rew_index")]
+    fn set_node_reward_index(&self, node_reward_index: &Self::BigUint);
+
+    /// The total cumulated rewards when the node reward index was last updated.
+    #[storage_get("node_rew_index_checkp")]
+    fn get_node_reward_index_checkpoint(&self) -> Self::BigUint;
+
+    #[storage_set("node_rew_index_checkp")]
+    fn set_node_reward_index_checkpoint(&self, checkpoint: &Self::BigUint);
+
+    /// Does not update storage, only yields the up to date node reward index.
+    fn load_updated_node_reward_index(&self) -> Self::BigUint {
+        let mut node_reward_index = self.get_node_reward_index();
+        let num_rewarded_nodes = self.count_rewarded_nodes();
+        if num_rewarded_nodes > 0 {
+            let mut new_rewards =
+                self.get_total_cumulated_rewards() - self.get_node_reward_index_checkpoint();
+            new_rewards /= Self::BigUint::from(num_rewarded_nodes);
+            node_reward_index += new_rewards;
+        }
+        node_reward_index
+    }
+
+    /// Must be called before every change in the number of rewarded nodes,
+    /// so that rewards are split between the nodes that were active when they came in.
+    fn update_node_reward_index(&self) -> Self::BigUint {
+        let node_reward_index = self.load_updated_node_reward_index();
+        self.set_node_reward_index(&node_reward_index);
+        self.set_node_reward_index_checkpoint(&self.get_total_cumulated_rewards());
+        node_reward_index
+    }
+
+    /// Rewards attributable to a node since it last became active.
+    /// Zero for nodes that are not currently earning rewards.
+    #[view(getNodeRewardShare)]
+    fn get_node_reward_share(&self, node_id: usize) -> Self::BigUint {
+        if node_id == 0 || node_id > self.num_nodes().get() || !self.is_node_rewarded(node_id) {
+            return Self::BigUint::zero();
+        }
+
+        self.load_updated_node_reward_index() - self.get_node_reward_checkpoint(node_id)
+    }
+
     #[view(getTotalUnProtected)]
     fn total_unprotected(&self) -> Self::BigUint {
         let sent_rewards = self.get_sent_rewards();
@@ -11,10 +11,28 @@ pub static PERCENTAGE_DENOMINATOR: usize = 10000;
 /// This can also count as a delegator (if the owner adds stake into the contract) or not.
 pub static OWNER_USER_ID: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(1) };
 
+/// Used while the owner has not set `setMaxNodesPerTx`.
+/// Each node adds its BLS key and signature to the auction call, so large batches can run out of gas.
+pub const DEFAULT_MAX_NODES_PER_TX: usize = 50;
+
+/// Lower bound for `setNumBlocksBeforeUnBond`: the unbond period of the protocol on mainnet,
+/// 10 epochs of 14400 blocks.
+pub const MIN_N_BLOCKS_BEFORE_UNBOND: u64 = 144_000;
+
+/// Roles yielded by `getAddressRole`.
+pub const ROLE_NONE: u8 = 0;
+pub const ROLE_OPERATOR: u8 = 1;
+pub const ROLE_OWNER: u8 = 2;
+
+/// Orders in which `proportionalUnstake` picks active nodes, see `setUnstakeNodeOrder`.
+pub const UNSTAKE_ORDER_BY_ID: u8 = 0;
+pub const UNSTAKE_ORDER_FIFO: u8 = 1;
+pub const UNSTAKE_ORDER_LIFO: u8 = 2;
+
 /// The module deals with initializaton and the global contract settings.
 ///
 #[elrond_wasm_derive::module]
-pub trait SettingsModule {
+pub trait SettingsModule: crate::events::EventsModule {
     /// Yields the address of the contract with which staking will be performed.
     /// This address is standard in the protocol, but it is saved in storage to avoid hardcoding it.
     #[view(getAuctionContractAddress)]
@@ -24,6 +42,49 @@ pub trait SettingsModule {
     #[storage_set("auction_addr")]
     fn set_auction_addr(&self, auction_addr: &Address);
 
+    /// Nodes can also be staked with other auction contracts than the main one.
+    /// These are indexed from 1, index 0 is the main auction contract.
+    #[storage_get("extra_auction_addr")]
+    fn get_extra_auction_addr(&self, auction_index: usize) -> Address;
+
+    #[storage_set("extra_auction_addr")]
+    fn set_extra_auction_addr(&self, auction_index: usize, auction_addr: &Address);
+
+    #[storage_get("num_extra_auction_addr")]
+    fn get_num_extra_auction_addr(&self) -> usize;
+
+    #[storage_set("num_extra_auction_addr")]
+    fn set_num_extra_auction_addr(&self, num_extra_auction_addr: usize);
+
+    /// Number of auction contracts, including the main one.
+    #[view(getNumAuctionContracts)]
+    fn get_num_auction_contracts(&self) -> usize {
+        self.get_num_extra_auction_addr() + 1
+    }
+
+    #[view(getAuctionContractAddressByIndex)]
+    fn get_auction_contract_address_by_index(&self, auction_index: usize) -> Address {
+        if auction_index == 0 {
+            self.get_auction_contract_address()
+        } else {
+            self.get_extra_auction_addr(auction_index)
+        }
+    }
+
+    /// Registers another auction contract. Yields its index.
+    #[endpoint(addAuctionContractAddress)]
+    fn add_auction_contract_address(&self, auction_addr: Address) -> SCResult<usize> {
+        only_owner!(self, "only owner can add auction contracts");
+        require!(
+            !auction_addr.is_zero(),
+            "auction contract address cannot be zero"
+        );
+        let auction_index = self.get_num_extra_auction_addr() + 1;
+        self.set_extra_auction_addr(auction_index, &auction_addr);
+        self.set_num_extra_auction_addr(auction_index);
+        Ok(auction_index)
+    }
+
     /// The proportion of rewards that goes to the owner as compensation for running the nodes.
     /// 10000 = 100%.
     #[view(getServiceFee)]
@@ -40,6 +101,201 @@ pub trait SettingsModule {
     #[storage_set("total_delegation_cap")]
     fn set_total_delegation_cap(&self, amount: Self::BigUint);
 
+    /// Upper limit for all the stake in the contract (waiting + active + unstaked).
+    /// Stake transactions that would exceed it are rejected.
+    /// Zero means unlimited.
+    #[view(getMaxDelegationCap)]
+    #[storage_get("max_delegation_cap")]
+    fn get_max_delegation_cap(&self) -> Self::BigUint;
+
+    #[storage_set("max_delegation_cap")]
+    fn set_max_delegation_cap(&self, max_delegation_cap: &Self::BigUint);
+
+    #[endpoint(setMaxDelegationCap)]
+    fn set_max_delegation_cap_endpoint(&self, max_delegation_cap: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can set max delegation cap");
+        self.set_max_delegation_cap(&max_delegation_cap);
+        Ok(())
+    }
+
+    /// New stake is rejected while delegations are paused.
+    /// Node operations and the other user operations are not affected.
+    #[view(areDelegationsPaused)]
+    #[storage_get("delegations_paused")]
+    fn are_delegations_paused(&self) -> bool;
+
+    #[storage_set("delegations_paused")]
+    fn set_delegations_paused(&self, delegations_paused: bool);
+
+    #[endpoint(pauseDelegations)]
+    fn pause_delegations(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can pause delegations");
+        self.set_delegations_paused(true);
+        Ok(())
+    }
+
+    #[endpoint(resumeDelegations)]
+    fn resume_delegations(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can resume delegations");
+        self.set_delegations_paused(false);
+        Ok(())
+    }
+
+    /// Freezes the global reward computation, e.g. while the reward logic is being migrated.
+    /// While frozen, global operations cannot start or continue, since they recompute all rewards.
+    /// A global operation already in progress stays in progress until rewards are unfrozen,
+    /// so the stake changes it pauses remain paused too.
+    /// Node operations are not affected, they never recompute all rewards.
+    #[view(areRewardsFrozen)]
+    #[storage_get("rewards_frozen")]
+    fn are_rewards_frozen(&self) -> bool;
+
+    #[storage_set("rewards_frozen")]
+    fn set_rewards_frozen(&self, rewards_frozen: bool);
+
+    #[endpoint(freezeRewards)]
+    fn freeze_rewards(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can freeze rewards");
+        self.set_rewards_frozen(true);
+        Ok(())
+    }
+
+    #[endpoint(unfreezeRewards)]
+    fn unfreeze_rewards(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can unfreeze rewards");
+        self.set_rewards_frozen(false);
+        Ok(())
+    }
+
+    /// Kill switch for all node operations, for incident response.
+    /// Also stops `compactUserFunds`.
+    /// Deposits are paused separately, via `pauseDelegations`,
+    /// and reward claims via the `claimRewards` feature flag.
+    #[view(isEmergencyPaused)]
+    #[storage_get("emergency_paused")]
+    fn is_emergency_paused(&self) -> bool;
+
+    #[storage_set("emergency_paused")]
+    fn set_emergency_paused(&self, emergency_paused: bool);
+
+    #[endpoint(emergencyPause)]
+    fn emergency_pause(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can emergency pause");
+        self.set_emergency_paused(true);
+        Ok(())
+    }
+
+    #[endpoint(emergencyUnpause)]
+    fn emergency_unpause(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can emergency unpause");
+        self.set_emergency_paused(false);
+        Ok(())
+    }
+
+    /// Number of stake callbacks in a row that reported failed nodes.
+    /// Reset by the first successful stake callback.
+    #[view(getStakeFailureCount)]
+    #[storage_get("stake_failure_count")]
+    fn get_stake_failure_count(&self) -> usize;
+
+    #[storage_set("stake_failure_count")]
+    fn set_stake_failure_count(&self, stake_failure_count: usize);
+
+    /// Staking is halted automatically once the stake failure count reaches this threshold.
+    /// Zero means disabled.
+    #[view(getStakeFailureThreshold)]
+    #[storage_get("stake_failure_threshold")]
+    fn get_stake_failure_threshold(&self) -> usize;
+
+    #[storage_set("stake_failure_threshold")]
+    fn set_stake_failure_threshold(&self, stake_failure_threshold: usize);
+
+    #[endpoint(setStakeFailureThreshold)]
+    fn set_stake_failure_threshold_endpoint(&self, stake_failure_threshold: usize) -> SCResult<()> {
+        only_owner!(self, "only owner can set stake failure threshold");
+        self.set_stake_failure_threshold(stake_failure_threshold);
+        Ok(())
+    }
+
+    /// While halted, `stakeNodes`, `stakeUpTo` and `stakeGroup` are rejected,
+    /// so that a keeper does not keep sending stake that the auction contract rejects.
+    #[view(isStakingHalted)]
+    #[storage_get("staking_halted")]
+    fn is_staking_halted(&self) -> bool;
+
+    #[storage_set("staking_halted")]
+    fn set_staking_halted(&self, staking_halted: bool);
+
+    /// Owner resumes staking after fixing the cause of the failures. Also resets the failure count.
+    #[endpoint(resumeStaking)]
+    fn resume_staking(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can resume staking");
+        self.set_staking_halted(false);
+        self.set_stake_failure_count(0);
+        Ok(())
+    }
+
+    /// Address allowed to manage nodes on behalf of the owner: stake, unstake, unbond, unjail and sync them.
+    /// Settings and funds stay owner-only. Zero if not set.
+    #[view(getOperator)]
+    #[storage_get("operator_address")]
+    fn get_operator(&self) -> Address;
+
+    #[storage_set("operator_address")]
+    fn set_operator(&self, operator: &Address);
+
+    /// Setting the zero address removes the operator.
+    #[endpoint(setOperator)]
+    fn set_operator_endpoint(&self, operator: Address) -> SCResult<()> {
+        only_owner!(self, "only owner can set the operator");
+        self.set_operator(&operator);
+        Ok(())
+    }
+
+    fn is_owner_or_operator(&self, address: &Address) -> bool {
+        self.get_address_role(address) != ROLE_NONE
+    }
+
+    /// 0 for regular addresses, 1 for the operator, 2 for the owner.
+    /// Endpoints restricted to the owner or operator accept roles 1 and 2, the other restricted endpoints only role 2.
+    #[view(getAddressRole)]
+    fn get_address_role(&self, address: &Address) -> u8 {
+        if *address == self.blockchain().get_owner_address() {
+            ROLE_OWNER
+        } else if !address.is_zero() && *address == self.get_operator() {
+            ROLE_OPERATOR
+        } else {
+            ROLE_NONE
+        }
+    }
+
+    /// While enabled, anyone can call `stakeAllAvailable`, not only the owner or operator.
+    #[view(isAutoActivationEnabled)]
+    #[storage_get("auto_activation_enabled")]
+    fn is_auto_activation_enabled(&self) -> bool;
+
+    #[storage_set("auto_activation_enabled")]
+    fn set_auto_activation_enabled(&self, auto_activation_enabled: bool);
+
+    #[endpoint(enableAutoActivation)]
+    fn enable_auto_activation(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can enable auto activation");
+        self.set_auto_activation_enabled(true);
+        Ok(())
+    }
+
+    #[endpoint(disableAutoActivation)]
+    fn disable_auto_activation(&self) -> SCResult<()> {
+        only_owner!(self, "only owner can disable auto activation");
+        self.set_auto_activation_enabled(false);
+        Ok(())
+    }
+
+    fn caller_can_activate(&self) -> bool {
+        self.is_auto_activation_enabled()
+            || self.is_owner_or_operator(&self.blockchain().get_caller())
+    }
+
     #[view(isBootstrapMode)]
     #[storage_get("bootstrap_mode")]
     fn is_bootstrap_mode(&self) -> bool;
@@ -47,7 +303,8 @@ pub trait SettingsModule {
     #[storage_set("bootstrap_mode")]
     fn set_bootstrap_mode(&self, bootstrap_mode: bool);
 
-    /// The minimum proportion of stake that has to be provided by the owner.
+    /// The minimum proportion of waiting + active stake that has to be provided by the owner
+    /// for nodes to be staked.
     /// 10000 = 100%.
     #[view(getOwnerMinStakeShare)]
     #[storage_get("owner_min_stake_share")]
@@ -69,7 +326,18 @@ pub trait SettingsModule {
         Ok(())
     }
 
+    #[endpoint(setOwnerMinStakeShare)]
+    fn set_owner_min_stake_share_endpoint(
+        &self,
+        owner_min_stake_share_per_10000: usize,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner can set owner min stake share");
+        self.set_owner_min_stake_share_validated(owner_min_stake_share_per_10000)
+    }
+
     /// Minimum number of n_blocks between unstake and fund getting into inactive state.
+    /// Cannot be set shorter than the unbond period of the protocol, see `MIN_N_BLOCKS_BEFORE_UNBOND`,
+    /// otherwise deferred payments become claimable before the stake is released.
     #[view(getNumBlocksBeforeUnBond)]
     #[storage_get("n_blocks_before_unbond")]
     fn get_n_blocks_before_unbond(&self) -> u64;
@@ -80,7 +348,156 @@ pub trait SettingsModule {
     #[endpoint(setNumBlocksBeforeUnBond)]
     fn set_n_blocks_before_unbond_endpoint(&self, n_blocks_before_unbond: u64) -> SCResult<()> {
         only_owner!(self, "only owner can set num blocks before unbond");
+        require!(
+            n_blocks_before_unbond >= MIN_N_BLOCKS_BEFORE_UNBOND,
+            "num blocks before unbond shorter than the protocol unbond period"
+        );
+        let n_blocks_before_force_unstake = self.get_n_blocks_before_force_unstake();
+        require!(
+            n_blocks_before_force_unstake == 0
+                || n_blocks_before_force_unstake >= n_blocks_before_unbond,
+            "num blocks before force unstake cannot be shorter than num blocks before unbond"
+        );
         self.set_n_blocks_before_unbond(n_blocks_before_unbond);
+        self.set_n_blocks_before_unbond_event(n_blocks_before_unbond);
+        Ok(())
+    }
+
+    /// Number of blocks a delegator has to wait after unstaking before being allowed to unstake nodes themselves.
+    /// Zero means disabled, otherwise it cannot be shorter than `getNumBlocksBeforeUnBond`.
+    #[view(getNumBlocksBeforeForceUnstake)]
+    #[storage_get("n_blocks_before_force_unstake")]
+    fn get_n_blocks_before_force_unstake(&self) -> u64;
+
+    #[storage_set("n_blocks_before_force_unstake")]
+    fn set_n_blocks_before_force_unstake(&self, n_blocks_before_force_unstake: u64);
+
+    #[endpoint(setNumBlocksBeforeForceUnstake)]
+    fn set_n_blocks_before_force_unstake_endpoint(
+        &self,
+        n_blocks_before_force_unstake: u64,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner can set num blocks before force unstake");
+        require!(
+            n_blocks_before_force_unstake == 0
+                || n_blocks_before_force_unstake >= self.get_n_blocks_before_unbond(),
+            "num blocks before force unstake cannot be shorter than num blocks before unbond"
+        );
+        self.set_n_blocks_before_force_unstake(n_blocks_before_force_unstake);
+        self.set_n_blocks_before_force_unstake_event(n_blocks_before_force_unstake);
+        Ok(())
+    }
+
+    /// 0 = lowest node id first (default), 1 = earliest activated first, 2 = latest activated first.
+    #[view(getUnstakeNodeOrder)]
+    #[storage_get("unstake_node_order")]
+    fn get_unstake_node_order(&self) -> u8;
+
+    #[storage_set("unstake_node_order")]
+    fn set_unstake_node_order(&self, unstake_node_order: u8);
+
+    #[endpoint(setUnstakeNodeOrder)]
+    fn set_unstake_node_order_endpoint(&self, unstake_node_order: u8) -> SCResult<()> {
+        only_owner!(self, "only owner can set the unstake node order");
+        require!(
+            unstake_node_order <= UNSTAKE_ORDER_LIFO,
+            "unknown unstake node order"
+        );
+        self.set_unstake_node_order(unstake_node_order);
+        Ok(())
+    }
+
+    #[storage_get("max_nodes_per_tx")]
+    fn get_max_nodes_per_tx_raw(&self) -> usize;
+
+    #[storage_set("max_nodes_per_tx")]
+    fn set_max_nodes_per_tx(&self, max_nodes_per_tx: usize);
+
+    /// Maximum number of BLS keys accepted by `stakeNodes`, `unStakeNodes`, `unStakeNodesAndTokens` and `unBondNodes`,
+    /// so that tooling can split larger batches into several transactions.
+    /// Endpoints that pick the nodes themselves, such as `stakeUpTo`, `stakeGroup` or `unBondAllPossibleNodes`,
+    /// stop at this many nodes.
+    #[view(getMaxNodesPerTx)]
+    fn get_max_nodes_per_tx(&self) -> usize {
+        let max_nodes_per_tx = self.get_max_nodes_per_tx_raw();
+        if max_nodes_per_tx == 0 {
+            DEFAULT_MAX_NODES_PER_TX
+        } else {
+            max_nodes_per_tx
+        }
+    }
+
+    #[endpoint(setMaxNodesPerTx)]
+    fn set_max_nodes_per_tx_endpoint(&self, max_nodes_per_tx: usize) -> SCResult<()> {
+        only_owner!(self, "only owner can set max nodes per transaction");
+        require!(
+            max_nodes_per_tx > 0,
+            "max nodes per transaction cannot be zero"
+        );
+        self.set_max_nodes_per_tx(max_nodes_per_tx);
+        Ok(())
+    }
+
+    fn validate_num_nodes_per_tx(&self, num_nodes: usize) -> SCResult<()> {
+        require!(
+            num_nodes <= self.get_max_nodes_per_tx(),
+            "too many nodes in one transaction, see getMaxNodesPerTx"
+        );
+        Ok(())
+    }
+
+    /// Number of blocks after unstaking during which a delegator can still take it back via `cancelUnstake`.
+    /// Zero means disabled.
+    #[view(getUnstakeGraceBlocks)]
+    #[storage_get("unstake_grace_blocks")]
+    fn get_unstake_grace_blocks(&self) -> u64;
+
+    #[storage_set("unstake_grace_blocks")]
+    fn set_unstake_grace_blocks(&self, unstake_grace_blocks: u64);
+
+    #[endpoint(setUnstakeGraceBlocks)]
+    fn set_unstake_grace_blocks_endpoint(&self, unstake_grace_blocks: u64) -> SCResult<()> {
+        only_owner!(self, "only owner can set unstake grace blocks");
+        self.set_unstake_grace_blocks(unstake_grace_blocks);
+        Ok(())
+    }
+
+    /// Number of blocks without activity after which a delegator with no funds left can be purged,
+    /// see `purgeInactiveUser`. Zero means purging is disabled.
+    #[view(getNumBlocksBeforePurge)]
+    #[storage_get("n_blocks_before_purge")]
+    fn get_n_blocks_before_purge(&self) -> u64;
+
+    #[storage_set("n_blocks_before_purge")]
+    fn set_n_blocks_before_purge(&self, n_blocks_before_purge: u64);
+
+    #[endpoint(setNumBlocksBeforePurge)]
+    fn set_n_blocks_before_purge_endpoint(&self, n_blocks_before_purge: u64) -> SCResult<()> {
+        only_owner!(self, "only owner can set num blocks before purge");
+        self.set_n_blocks_before_purge(n_blocks_before_purge);
+        Ok(())
+    }
+
+    /// Number of blocks after which the owner can cancel a node activation
+    /// for which the auction contract never called back.
+    /// Zero means cancelling is disabled.
+    #[view(getNumBlocksBeforeCancelActivation)]
+    #[storage_get("n_blocks_before_cancel_activation")]
+    fn get_n_blocks_before_cancel_activation(&self) -> u64;
+
+    #[storage_set("n_blocks_before_cancel_activation")]
+    fn set_n_blocks_before_cancel_activation(&self, n_blocks_before_cancel_activation: u64);
+
+    #[endpoint(setNumBlocksBeforeCancelActivation)]
+    fn set_n_blocks_before_cancel_activation_endpoint(
+        &self,
+        n_blocks_before_cancel_activation: u64,
+    ) -> SCResult<()> {
+        only_owner!(
+            self,
+            "only owner can set num blocks before cancel activation"
+        );
+        self.set_n_blocks_before_cancel_activation(n_blocks_before_cancel_activation);
         Ok(())
     }
 
@@ -99,4 +516,85 @@ pub trait SettingsModule {
         self.set_minimum_stake(&minimum_stake);
         Ok(())
     }
+
+    /// The first stake of a delegator must be at least this amount.
+    /// Delegators that already have stake in the contract can top up with less.
+    /// Zero means disabled.
+    #[view(getMinDelegation)]
+    #[storage_get("min_delegation")]
+    fn get_min_delegation(&self) -> Self::BigUint;
+
+    #[storage_set("min_delegation")]
+    fn set_min_delegation(&self, min_delegation: &Self::BigUint);
+
+    #[endpoint(setMinDelegation)]
+    fn set_min_delegation_endpoint(&self, min_delegation: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can set min delegation");
+        self.set_min_delegation(&min_delegation);
+        Ok(())
+    }
+
+    /// Unprotected funds that staking nodes never uses, kept aside in the contract balance.
+    /// Still counted in `getTotalUnProtected`.
+    #[view(getFeeReserve)]
+    #[storage_get("fee_reserve")]
+    fn get_fee_reserve(&self) -> Self::BigUint;
+
+    #[storage_set("fee_reserve")]
+    fn set_fee_reserve(&self, fee_reserve: &Self::BigUint);
+
+    #[endpoint(setFeeReserve)]
+    fn set_fee_reserve_endpoint(&self, fee_reserve: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can set fee reserve");
+        self.set_fee_reserve(&fee_reserve);
+        Ok(())
+    }
+
+    /// Token accepted by `stake` and sent to the auction contract when staking nodes.
+    /// EGLD unless configured otherwise.
+    #[view(getStakingToken)]
+    fn get_staking_token(&self) -> TokenIdentifier {
+        if self.is_empty_staking_token() {
+            TokenIdentifier::egld()
+        } else {
+            self.get_stored_staking_token()
+        }
+    }
+
+    #[storage_get("staking_token")]
+    fn get_stored_staking_token(&self) -> TokenIdentifier;
+
+    #[storage_set("staking_token")]
+    fn set_staking_token(&self, staking_token: &TokenIdentifier);
+
+    #[storage_is_empty("staking_token")]
+    fn is_empty_staking_token(&self) -> bool;
+
+    /// The auction contract only accepts EGLD, so no other token can be configured for now.
+    #[endpoint(setStakingToken)]
+    fn set_staking_token_endpoint(&self, staking_token: TokenIdentifier) -> SCResult<()> {
+        only_owner!(self, "only owner can set the staking token");
+        require!(
+            staking_token.is_egld(),
+            "the auction contract only accepts EGLD"
+        );
+        self.set_staking_token(&staking_token);
+        Ok(())
+    }
+
+    /// The amount of EGLD the auction contract requires for each staked node.
+    /// Used when the contract picks by itself how many nodes to stake.
+    #[view(getStakePerNode)]
+    #[storage_get("stake_per_node")]
+    fn get_stake_per_node(&self) -> Self::BigUint;
+
+    #[storage_set("stake_per_node")]
+    fn set_stake_per_node(&self, stake_per_node: &Self::BigUint);
+
+    #[endpoint(setStakePerNode)]
+    fn set_stake_per_node_endpoint(&self, stake_per_node: Self::BigUint) -> SCResult<()> {
+        only_owner!(self, "only owner can set stake per node");
+        self.set_stake_per_node(&stake_per_node);
+        Ok(())
+    }
 }
@@ -112,4 +112,52 @@ pub trait UserStakeDustCleanupModule:
 
         Ok(())
     }
+
+    /// Deletes the user data of a delegator that fully exited:
+    /// no funds of any type, no unclaimed rewards,
+    /// and no activity for at least `getNumBlocksBeforePurge` blocks.
+    /// The user id is not reused; if the delegator comes back, they get a new one.
+    #[endpoint(purgeInactiveUser)]
+    fn purge_inactive_user(&self, user_address: Address) -> SCResult<()> {
+        only_owner!(self, "only owner can purge inactive users");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "contract is temporarily paused as checkpoint is reset"
+        );
+
+        let n_blocks_before_purge = self.get_n_blocks_before_purge();
+        require!(
+            n_blocks_before_purge > 0,
+            "purging inactive users is disabled"
+        );
+
+        let user_id = non_zero_usize!(self.get_user_id(&user_address), "unknown user");
+        require!(user_id != OWNER_USER_ID, "cannot purge the owner");
+
+        require!(
+            FundType::ALL_TYPES
+                .iter()
+                .all(|&fund_type| self.get_user_stake_of_type(user_id.get(), fund_type) == 0),
+            "user still has funds"
+        );
+
+        require!(
+            self.load_updated_user_rewards(user_id).unclaimed_rewards == 0,
+            "user still has unclaimed rewards"
+        );
+
+        let last_activity = self.get_user_last_activity_nonce(user_id.get());
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        require!(
+            last_activity
+                .checked_add(n_blocks_before_purge)
+                .map_or(false, |purge_nonce| current_bl_nonce >= purge_nonce),
+            "user not inactive long enough"
+        );
+
+        self.clear_user_data(user_id.get(), &user_address);
+
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 use crate::settings::OWNER_USER_ID;
 use core::num::NonZeroUsize;
-use user_fund_storage::types::FundType;
+use node_storage::types::NodeState;
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::{FundDescription, FundType};
 
 elrond_wasm::imports!();
 
@@ -22,11 +24,23 @@ pub trait UserStakeEndpointsModule:
 {
     /// Delegate stake to the smart contract.
     /// Stake is initially inactive, so does it not produce rewards.
-    #[payable("EGLD")]
+    /// Only the staking token is accepted, see `getStakingToken`.
+    #[payable("*")]
     #[endpoint(stake)]
-    fn stake_endpoint(&self, #[payment] payment: Self::BigUint) -> SCResult<()> {
+    fn stake_endpoint(
+        &self,
+        #[payment] payment: Self::BigUint,
+        #[payment_token] payment_token: TokenIdentifier,
+    ) -> SCResult<()> {
         require!(self.not_paused(), "contract paused");
 
+        require!(
+            payment_token == self.get_staking_token(),
+            "payment token is not the staking token"
+        );
+
+        require!(!self.are_delegations_paused(), "delegations paused");
+
         require!(
             payment >= self.get_minimum_stake(),
             "cannot stake less than minimum stake"
@@ -37,9 +51,50 @@ pub trait UserStakeEndpointsModule:
             "staking is temporarily paused as checkpoint is reset"
         );
 
+        self.validate_min_delegation(&payment)?;
+        self.validate_max_delegation_cap(&payment)?;
+
         self.process_stake(payment)
     }
 
+    /// Only applies to delegators that do not have any stake in the contract yet.
+    fn validate_min_delegation(&self, payment: &Self::BigUint) -> SCResult<()> {
+        if *payment >= self.get_min_delegation() {
+            return Ok(());
+        }
+
+        let user_id = self.get_user_id(&self.blockchain().get_caller());
+        let has_stake = user_id > 0
+            && FundType::ALL_TYPES
+                .iter()
+                .filter(|fund_type| fund_type.is_stake())
+                .any(|&fund_type| self.get_user_stake_of_type(user_id, fund_type) > 0);
+        require!(has_stake, "first stake cannot be less than min delegation");
+        Ok(())
+    }
+
+    /// Rejects stake that would take the contract over the max delegation cap.
+    fn validate_max_delegation_cap(&self, payment: &Self::BigUint) -> SCResult<()> {
+        require!(
+            !self.exceeds_max_delegation_cap(payment),
+            "stake would exceed the max delegation cap"
+        );
+        Ok(())
+    }
+
+    fn exceeds_max_delegation_cap(&self, payment: &Self::BigUint) -> bool {
+        let max_delegation_cap = self.get_max_delegation_cap();
+        if max_delegation_cap == 0 {
+            return false;
+        }
+
+        let mut total_stake = payment.clone();
+        total_stake += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
+        total_stake += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+        total_stake += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+        total_stake > max_delegation_cap
+    }
+
     /// unStake - the user will announce that he wants to get out of the contract
     /// selected funds will change from active to inactive, but claimable only after unBond period ends
     #[endpoint(unStake)]
@@ -54,6 +109,7 @@ pub trait UserStakeEndpointsModule:
         let caller = self.blockchain().get_caller();
         let unstake_user_id =
             non_zero_usize!(self.get_user_id(&caller), "only delegators can unstake");
+        self.record_user_activity(unstake_user_id.get());
 
         // validate that amount does not exceed existing waiting + active stake
         self.validate_unstake_amount(unstake_user_id.get(), &amount)?;
@@ -70,6 +126,9 @@ pub trait UserStakeEndpointsModule:
         self.compute_one_user_reward(OWNER_USER_ID);
         self.compute_one_user_reward(unstake_user_id);
 
+        let had_unstaked =
+            self.get_user_stake_of_type(unstake_user_id.get(), FundType::UnStaked) > 0;
+
         // convert Active -> UnStaked
         self.swap_user_active_to_unstaked(unstake_user_id.get(), &mut remaining);
         require!(remaining == 0, "error converting Active to UnStaked");
@@ -80,9 +139,92 @@ pub trait UserStakeEndpointsModule:
         // check that minimum stake was not violated
         self.validate_user_minimum_stake(unstake_user_id.get())?;
 
+        // stake not replaced by waiting stake starts the force unstake countdown
+        if !had_unstaked
+            && self.get_user_stake_of_type(unstake_user_id.get(), FundType::UnStaked) > 0
+        {
+            self.set_user_unstake_request_nonce(
+                unstake_user_id.get(),
+                self.blockchain().get_block_nonce(),
+            );
+            self.clear_user_force_unstaked(unstake_user_id.get());
+        }
+
         Ok(())
     }
 
+    /// Delegators can take back their unstake requests made in the last `getUnstakeGraceBlocks` blocks,
+    /// turning the UnStaked funds created in that window back to Active.
+    /// Older UnStaked funds and funds that already became deferred payments are not affected.
+    /// Nodes are not restaked: the active nodes must cover all the active stake, including the reactivated one,
+    /// minus the tokens already unstaked from the auction contract via `unStakeTokensAmount`,
+    /// otherwise the cancellation is rejected.
+    /// Yields the amount reactivated.
+    #[endpoint(cancelUnstake)]
+    fn cancel_unstake(&self) -> SCResult<Self::BigUint> {
+        require!(self.not_paused(), "contract paused");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "cancelling unstake is temporarily paused as checkpoint is reset"
+        );
+
+        let unstake_grace_blocks = self.get_unstake_grace_blocks();
+        require!(unstake_grace_blocks > 0, "cancel unstake disabled");
+
+        let caller = self.blockchain().get_caller();
+        let user_id = non_zero_usize!(self.get_user_id(&caller), "unknown caller");
+        self.record_user_activity(user_id.get());
+
+        let user_unstaked = self.get_user_stake_of_type(user_id.get(), FundType::UnStaked);
+        require!(user_unstaked > 0, "no unstaked stake to reactivate");
+
+        let min_created = self
+            .blockchain()
+            .get_block_nonce()
+            .saturating_sub(unstake_grace_blocks);
+        let cancellable =
+            self.query_sum_funds_by_user_type(user_id.get(), FundType::UnStaked, |fund_desc| {
+                matches!(fund_desc, FundDescription::UnStaked { created } if created >= min_created)
+            });
+        require!(cancellable > 0, "unstake grace period has passed");
+
+        let stake_per_node = self.get_stake_per_node();
+        require!(stake_per_node > 0, "stake per node not set");
+
+        let active_node_ids: Vec<usize> = (1..=self.num_nodes().get())
+            .filter(|&node_id| self.get_node_state(node_id) == NodeState::Active)
+            .collect();
+        let mut active_nodes_stake = self.get_nodes_stake(&active_node_ids, &stake_per_node);
+        let unstaked_tokens = self.get_unstaked_tokens();
+        if active_nodes_stake > unstaked_tokens {
+            active_nodes_stake -= &unstaked_tokens;
+        } else {
+            active_nodes_stake = Self::BigUint::zero();
+        }
+        let mut total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+        total_active += &cancellable;
+        require!(
+            total_active <= active_nodes_stake,
+            "not enough active nodes to cancel unstake, unstaked nodes are not restaked"
+        );
+
+        // compute rewards before converting UnStaked -> Active
+        self.compute_one_user_reward(OWNER_USER_ID);
+        self.compute_one_user_reward(user_id);
+
+        let reactivated =
+            self.swap_user_unstaked_created_since_to_active(user_id.get(), min_created);
+
+        // nothing left to force unstake for
+        if self.get_user_stake_of_type(user_id.get(), FundType::UnStaked) == 0 {
+            self.set_user_unstake_request_nonce(user_id.get(), 0);
+            self.clear_user_force_unstaked(user_id.get());
+        }
+
+        Ok(reactivated)
+    }
+
     #[view(getUnStakeable)]
     fn get_unstakeable(&self, user_address: Address) -> Self::BigUint {
         let user_id = self.get_user_id(&user_address);
@@ -101,6 +243,7 @@ pub trait UserStakeEndpointsModule:
         let caller = self.blockchain().get_caller();
         let caller_id = self.get_user_id(&caller);
         require!(caller_id > 0, "unknown caller");
+        self.record_user_activity(caller_id);
 
         let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
         let _ = self.swap_eligible_deferred_to_withdraw(caller_id, n_blocks_before_unbond, || {
@@ -120,6 +263,76 @@ pub trait UserStakeEndpointsModule:
         Ok(amount_liquidated)
     }
 
+    /// Same as `unBond`, but only claims the deferred payments created before `before_nonce`,
+    /// so that the caller can choose which payments to realize.
+    /// Withdraw-only funds are paid out as well.
+    #[endpoint(claimDeferredBefore)]
+    fn claim_deferred_before(&self, before_nonce: u64) -> SCResult<Self::BigUint> {
+        require!(self.not_paused(), "contract paused");
+
+        let caller = self.blockchain().get_caller();
+        let caller_id = self.get_user_id(&caller);
+        require!(caller_id > 0, "unknown caller");
+        self.record_user_activity(caller_id);
+
+        require!(
+            before_nonce <= self.blockchain().get_block_nonce(),
+            "before nonce cannot be in the future"
+        );
+
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let _ = self.swap_eligible_deferred_created_before_to_withdraw(
+            caller_id,
+            n_blocks_before_unbond,
+            before_nonce,
+            || self.blockchain().get_gas_left() < UNBOND_GASLIMIT,
+        );
+
+        let amount_liquidated = self.liquidate_all_withdraw_only(caller_id, || {
+            self.blockchain().get_gas_left() < UNBOND_GASLIMIT
+        });
+
+        if amount_liquidated > 0 {
+            self.send()
+                .direct_egld(&caller, &amount_liquidated, b"delegation deferred claim");
+        }
+
+        Ok(amount_liquidated)
+    }
+
+    /// Withdraws all the waiting stake of the caller, along with any withdraw-only funds.
+    /// Waiting stake was never activated, so there is no unbond period.
+    /// Active stake is not affected, it still needs to go through unStake and unBond.
+    #[endpoint(withdrawWaiting)]
+    fn withdraw_waiting(&self) -> SCResult<Self::BigUint> {
+        require!(self.not_paused(), "contract paused");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "withdrawing is temporarily paused as checkpoint is reset"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let caller_id = self.get_user_id(&caller);
+        require!(caller_id > 0, "unknown caller");
+        self.record_user_activity(caller_id);
+
+        let mut remaining = self.get_user_stake_of_type(caller_id, FundType::Waiting);
+        self.swap_user_waiting_to_withdraw_only(caller_id, &mut remaining);
+        require!(remaining == 0, "error converting Waiting to WithdrawOnly");
+
+        // check that minimum stake was not violated
+        self.validate_user_minimum_stake(caller_id)?;
+
+        let amount_liquidated = self.liquidate_all_withdraw_only(caller_id, || false);
+        if amount_liquidated > 0 {
+            self.send()
+                .direct_egld(&caller, &amount_liquidated, b"delegation waiting withdraw");
+        }
+
+        Ok(amount_liquidated)
+    }
+
     #[view(getUnBondable)]
     fn get_unbondable(&self, user_address: Address) -> Self::BigUint {
         let user_id = self.get_user_id(&user_address);
@@ -131,4 +344,168 @@ pub trait UserStakeEndpointsModule:
                 + self.get_user_stake_of_type(user_id, FundType::WithdrawOnly)
         }
     }
+
+    /// Deferred payment of a user, split into what can be claimed now
+    /// and what is still locked in the unbond period.
+    #[view(getClaimableDeferredPayment)]
+    fn get_claimable_deferred_payment(
+        &self,
+        user_address: Address,
+    ) -> MultiResult2<Self::BigUint, Self::BigUint> {
+        let user_id = self.get_user_id(&user_address);
+        if user_id == 0 {
+            return (Self::BigUint::zero(), Self::BigUint::zero()).into();
+        }
+
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let claimable = self.eligible_deferred_payment(user_id, n_blocks_before_unbond);
+        let total_deferred = self.get_user_stake_of_type(user_id, FundType::DeferredPayment);
+        let locked = &total_deferred - &claimable;
+        (claimable, locked).into()
+    }
+
+    /// Delegators opt in (or out) of having their matured deferred payments restaked.
+    #[endpoint(setAutoRestake)]
+    fn set_auto_restake(&self, auto_restake: bool) -> SCResult<()> {
+        let caller = self.blockchain().get_caller();
+        let user_id = self.get_user_id(&caller);
+        require!(user_id > 0, "unknown caller");
+
+        self.set_user_auto_restake(user_id, auto_restake);
+        Ok(())
+    }
+
+    #[view(isAutoRestake)]
+    fn is_auto_restake(&self, user_address: Address) -> bool {
+        let user_id = self.get_user_id(&user_address);
+        user_id > 0 && self.get_user_auto_restake(user_id)
+    }
+
+    /// Id of the last user checked by a `restakeMatured` call that ran out of gas.
+    /// Zero if the last call went through all users.
+    #[view(getRestakeMaturedCursor)]
+    #[storage_mapper("restake_matured_cursor")]
+    fn restake_matured_cursor(&self) -> SingleValueMapper<Self::Storage, usize>;
+
+    /// Converts the eligible deferred payments of all users that opted in back into waiting stake.
+    /// Can be called by anyone. Stops early if running out of gas,
+    /// calling again continues after the last user checked, see `getRestakeMaturedCursor`.
+    /// Users whose restake would exceed the max delegation cap are skipped.
+    /// Yields the number of users whose funds were restaked.
+    #[endpoint(restakeMatured)]
+    fn restake_matured(&self) -> SCResult<usize> {
+        require!(self.not_paused(), "contract paused");
+
+        require!(!self.are_delegations_paused(), "delegations paused");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "restaking is temporarily paused as checkpoint is reset"
+        );
+
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let num_users = self.get_num_users();
+        let mut num_restaked = 0;
+        let mut last_checked_user_id = 0;
+        for user_id in (self.restake_matured_cursor().get() + 1)..=num_users {
+            if self.blockchain().get_gas_left() < UNBOND_GASLIMIT {
+                last_checked_user_id = user_id - 1;
+                break;
+            }
+
+            if !self.get_user_auto_restake(user_id) {
+                continue;
+            }
+
+            let eligible = self.eligible_deferred_payment(user_id, n_blocks_before_unbond);
+            if eligible == 0 || self.exceeds_max_delegation_cap(&eligible) {
+                continue;
+            }
+
+            let amount_restaked =
+                self.swap_eligible_deferred_to_waiting(user_id, n_blocks_before_unbond, || false);
+            self.restake_event(&self.get_user_address(user_id), &amount_restaked);
+            num_restaked += 1;
+        }
+
+        // resume from here next time, or start over after a full pass
+        if last_checked_user_id > 0 {
+            self.restake_matured_cursor().set(&last_checked_user_id);
+        } else {
+            self.restake_matured_cursor().clear();
+        }
+
+        // check invariant
+        self.validate_delegation_cap_invariant()?;
+
+        // move funds around
+        self.use_waiting_to_replace_unstaked()?;
+
+        Ok(num_restaked)
+    }
+
+    /// Merges the fragmented fund items of a given type of the caller, freeing storage.
+    /// Balances do not change. Only the caller's own funds can be compacted,
+    /// since merging items also moves stake within the lists of all users.
+    /// Yields the number of fund items removed.
+    #[endpoint(compactUserFunds)]
+    fn compact_user_funds_endpoint(&self, fund_type: FundType) -> SCResult<usize> {
+        require!(
+            !self.is_emergency_paused(),
+            "compacting funds is paused for emergency"
+        );
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "compacting funds is temporarily paused as checkpoint is reset"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let user_id = self.get_user_id(&caller);
+        require!(user_id > 0, "unknown caller");
+
+        Ok(self.compact_user_funds(user_id, fund_type))
+    }
+
+    /// Turns all the withdraw-only funds of the caller back into waiting stake,
+    /// instead of withdrawing them and staking again.
+    /// The restaked amount is subject to the same limits as a new stake.
+    /// Yields the amount restaked.
+    #[endpoint(restakeUnbonded)]
+    fn restake_unbonded(&self) -> SCResult<Self::BigUint> {
+        require!(self.not_paused(), "contract paused");
+
+        require!(!self.are_delegations_paused(), "delegations paused");
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "restaking is temporarily paused as checkpoint is reset"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let caller_id = self.get_user_id(&caller);
+        require!(caller_id > 0, "unknown caller");
+        self.record_user_activity(caller_id);
+
+        let withdraw_only = self.get_user_stake_of_type(caller_id, FundType::WithdrawOnly);
+        require!(withdraw_only > 0, "no unbonded funds to restake");
+        require!(
+            withdraw_only >= self.get_minimum_stake(),
+            "cannot restake less than minimum stake"
+        );
+
+        self.validate_min_delegation(&withdraw_only)?;
+        self.validate_max_delegation_cap(&withdraw_only)?;
+
+        let amount_restaked = self.swap_user_withdraw_only_to_waiting(caller_id);
+        self.restake_event(&caller, &amount_restaked);
+
+        // check invariant
+        self.validate_delegation_cap_invariant()?;
+
+        // move funds around
+        self.use_waiting_to_replace_unstaked()?;
+
+        Ok(amount_restaked)
+    }
 }
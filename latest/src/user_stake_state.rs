@@ -25,6 +25,7 @@ pub trait UserStakeStateModule:
         // because we might at some point need to iterate over all user data
         let caller = self.blockchain().get_caller();
         let user_id = self.get_or_create_user(&caller);
+        self.record_user_activity(user_id);
 
         // log staking event
         self.stake_event(&caller, &payment);
@@ -130,21 +131,72 @@ pub trait UserStakeStateModule:
         Ok(())
     }
 
-    /// Invariant: should never return error.
+    /// Waiting + active stake, the stake that counts towards the owner stake share.
+    fn get_user_stake_for_share(&self, user_id: usize) -> Self::BigUint {
+        self.get_user_stake_of_type(user_id, FundType::Waiting)
+            + self.get_user_stake_of_type(user_id, FundType::Active)
+    }
+
+    /// Checked before staking nodes. Can fail if the owner raises the min stake share,
+    /// until the owner adds more stake.
     #[view(validateOwnerStakeShare)]
     fn validate_owner_stake_share(&self) -> SCResult<()> {
         // owner total stake / contract total stake < owner_min_stake_share / 10000
         // reordered to avoid divisions
         require!(
-            self.get_user_stake_of_type(OWNER_USER_ID.get(), FundType::Active)
+            self.get_user_stake_for_share(OWNER_USER_ID.get())
                 * Self::BigUint::from(PERCENTAGE_DENOMINATOR)
-                >= self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active)
+                >= self.get_user_stake_for_share(USER_STAKE_TOTALS_ID)
                     * self.get_owner_min_stake_share(),
             "owner doesn't have enough stake in the contract"
         );
         Ok(())
     }
 
+    /// The current proportion of waiting + active stake provided by the owner.
+    /// 10000 = 100%, same as `getOwnerMinStakeShare`.
+    #[view(getOwnerStakeShare)]
+    fn get_owner_stake_share(&self) -> Self::BigUint {
+        let total_stake = self.get_user_stake_for_share(USER_STAKE_TOTALS_ID);
+        if total_stake == 0 {
+            return Self::BigUint::zero();
+        }
+        self.get_user_stake_for_share(OWNER_USER_ID.get())
+            * Self::BigUint::from(PERCENTAGE_DENOMINATOR)
+            / total_stake
+    }
+
+    /// Where the user stands in the waiting queue. Yields, in order:
+    /// - the waiting stake created before the first waiting stake of the user,
+    /// - how much waiting stake could be activated right now, i.e. the unfilled part of the total delegation cap.
+    /// Waiting stake is activated in creation order, so the user's stake starts activating
+    /// once the delegation cap frees up by more than the first amount.
+    /// Yields nothing if the user has no waiting stake.
+    #[view(getWaitingQueuePosition)]
+    fn get_waiting_queue_position(
+        &self,
+        user_address: Address,
+    ) -> OptionalResult<MultiResult2<Self::BigUint, Self::BigUint>> {
+        let user_id = self.get_user_id(&user_address);
+        if user_id == 0 {
+            return OptionalResult::None;
+        }
+        match self.query_sum_funds_ahead_of_user(FundType::Waiting, user_id) {
+            Some(waiting_ahead) => {
+                let total_delegation_cap = self.get_total_delegation_cap();
+                let total_active =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+                let activatable_now = if total_delegation_cap > total_active {
+                    total_delegation_cap - total_active
+                } else {
+                    Self::BigUint::zero()
+                };
+                OptionalResult::Some((waiting_ahead, activatable_now).into())
+            }
+            None => OptionalResult::None,
+        }
+    }
+
     fn validate_unstake_amount(&self, user_id: usize, amount: &Self::BigUint) -> SCResult<()> {
         let max_unstake = self.get_user_stake_of_type(user_id, FundType::Waiting)
             + self.get_user_stake_of_type(user_id, FundType::Active);
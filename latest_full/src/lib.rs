@@ -22,6 +22,7 @@ pub trait DelegationFull:
     + delegation_latest::rewards_endpoints::RewardEndpointsModule
     + delegation_latest::user_stake_endpoints::UserStakeEndpointsModule
     + delegation_latest::user_stake_dust_cleanup::UserStakeDustCleanupModule
+    + delegation_latest::owner_reserve::OwnerReserveModule
     + delegation_latest::elrond_wasm_module_dns::DnsModule
     + delegation_latest::elrond_wasm_module_features::FeaturesModule
     + delegation_latest::elrond_wasm_module_pause::PauseModule
@@ -33,6 +34,14 @@ pub trait DelegationFull:
         env!("CARGO_PKG_VERSION")
     }
 
+    /// The version, followed by capability flags, so that tooling does not need to probe endpoints:
+    /// supports `unStakeNodesAndTokens`, supports bootstrap mode, supports checkpoint resets.
+    /// The 0.4 contracts have neither this view nor any of these capabilities.
+    #[view(getContractVersion)]
+    fn get_contract_version(&self) -> MultiResult4<&'static str, bool, bool, bool> {
+        (env!("CARGO_PKG_VERSION"), true, true, true).into()
+    }
+
     // INIT
 
     /// This is the contract constructor, called only once when the contract is deployed.
@@ -63,6 +72,10 @@ pub trait DelegationFull:
 
         self.set_owner_min_stake_share_validated(owner_min_stake_share_per_10000)?;
 
+        require!(
+            n_blocks_before_unbond > 0,
+            "num blocks before unbond cannot be zero"
+        );
         self.set_n_blocks_before_unbond(n_blocks_before_unbond);
         self.set_minimum_stake(&minimum_stake);
 
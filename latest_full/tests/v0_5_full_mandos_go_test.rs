@@ -3,16 +3,46 @@ fn activate_nodes_go() {
     elrond_wasm_debug::mandos_go("mandos/activate_nodes.scen.json");
 }
 
+#[test]
+fn cancel_pending_activation_go() {
+    elrond_wasm_debug::mandos_go("mandos/cancel_pending_activation.scen.json");
+}
+
+#[test]
+fn cancel_unstake_go() {
+    elrond_wasm_debug::mandos_go("mandos/cancel_unstake.scen.json");
+}
+
 #[test]
 fn change_service_fee_go() {
     elrond_wasm_debug::mandos_go("mandos/change_service_fee.scen.json");
 }
 
+#[test]
+fn claim_deferred_before_go() {
+    elrond_wasm_debug::mandos_go("mandos/claim_deferred_before.scen.json");
+}
+
+#[test]
+fn claim_failed_stake_go() {
+    elrond_wasm_debug::mandos_go("mandos/claim_failed_stake.scen.json");
+}
+
 #[test]
 fn claim_rewards_1_go() {
     elrond_wasm_debug::mandos_go("mandos/claim_rewards_1.scen.json");
 }
 
+#[test]
+fn claim_rewards_amount_go() {
+    elrond_wasm_debug::mandos_go("mandos/claim_rewards_amount.scen.json");
+}
+
+#[test]
+fn claim_rewards_for_users_go() {
+    elrond_wasm_debug::mandos_go("mandos/claim_rewards_for_users.scen.json");
+}
+
 #[test]
 fn claim_rewards_owner_with_stake_go() {
     elrond_wasm_debug::mandos_go("mandos/claim_rewards_owner_with_stake.scen.json");
@@ -33,6 +63,11 @@ fn claim_rewards_with_stake_modifications_go() {
     elrond_wasm_debug::mandos_go("mandos/claim_rewards_with_stake_modifications.scen.json");
 }
 
+#[test]
+fn compact_user_funds_go() {
+    elrond_wasm_debug::mandos_go("mandos/compact_user_funds.scen.json");
+}
+
 #[test]
 fn continue_global_operations_go() {
     elrond_wasm_debug::mandos_go("mandos/continue_global_operations.scen.json");
@@ -43,21 +78,176 @@ fn decrease_cap_in_bootstrap_mode_go() {
     elrond_wasm_debug::mandos_go("mandos/decrease_cap_in_bootstrap_mode.scen.json");
 }
 
+#[test]
+fn emergency_pause_go() {
+    elrond_wasm_debug::mandos_go("mandos/emergency_pause.scen.json");
+}
+
+#[test]
+fn end_bootstrap_mode_go() {
+    elrond_wasm_debug::mandos_go("mandos/end_bootstrap_mode.scen.json");
+}
+
+#[test]
+fn estimated_apr_go() {
+    elrond_wasm_debug::mandos_go("mandos/estimated_apr.scen.json");
+}
+
+#[test]
+fn fee_reserve_go() {
+    elrond_wasm_debug::mandos_go("mandos/fee_reserve.scen.json");
+}
+
+#[test]
+fn force_unstake_go() {
+    elrond_wasm_debug::mandos_go("mandos/force_unstake.scen.json");
+}
+
 #[test]
 fn increase_delegation_cap_go() {
     elrond_wasm_debug::mandos_go("mandos/increase_delegation_cap.scen.json");
 }
 
+#[test]
+fn max_delegation_cap_go() {
+    elrond_wasm_debug::mandos_go("mandos/max_delegation_cap.scen.json");
+}
+
+#[test]
+fn max_nodes_per_tx_go() {
+    elrond_wasm_debug::mandos_go("mandos/max_nodes_per_tx.scen.json");
+}
+
+#[test]
+fn min_delegation_go() {
+    elrond_wasm_debug::mandos_go("mandos/min_delegation.scen.json");
+}
+
+#[test]
+fn multiple_auctions_go() {
+    elrond_wasm_debug::mandos_go("mandos/multiple_auctions.scen.json");
+}
+
+#[test]
+fn node_groups_go() {
+    elrond_wasm_debug::mandos_go("mandos/node_groups.scen.json");
+}
+
+#[test]
+fn node_history_go() {
+    elrond_wasm_debug::mandos_go("mandos/node_history.scen.json");
+}
+
+#[test]
+fn node_metadata_go() {
+    elrond_wasm_debug::mandos_go("mandos/node_metadata.scen.json");
+}
+
+#[test]
+fn node_reward_share_go() {
+    elrond_wasm_debug::mandos_go("mandos/node_reward_share.scen.json");
+}
+
+#[test]
+fn node_stake_go() {
+    elrond_wasm_debug::mandos_go("mandos/node_stake.scen.json");
+}
+
+#[test]
+fn operator_go() {
+    elrond_wasm_debug::mandos_go("mandos/operator.scen.json");
+}
+
+#[test]
+fn owner_min_stake_share_go() {
+    elrond_wasm_debug::mandos_go("mandos/owner_min_stake_share.scen.json");
+}
+
+#[test]
+fn owner_reserve_go() {
+    elrond_wasm_debug::mandos_go("mandos/owner_reserve.scen.json");
+}
+
+#[test]
+fn pause_delegations_go() {
+    elrond_wasm_debug::mandos_go("mandos/pause_delegations.scen.json");
+}
+
+#[test]
+fn proportional_unstake_go() {
+    elrond_wasm_debug::mandos_go("mandos/proportional_unstake.scen.json");
+}
+
+#[test]
+fn purge_inactive_user_go() {
+    elrond_wasm_debug::mandos_go("mandos/purge_inactive_user.scen.json");
+}
+
+#[test]
+fn restake_matured_go() {
+    elrond_wasm_debug::mandos_go("mandos/restake_matured.scen.json");
+}
+
+#[test]
+fn restake_unbonded_go() {
+    elrond_wasm_debug::mandos_go("mandos/restake_unbonded.scen.json");
+}
+
+#[test]
+fn reward_address_go() {
+    elrond_wasm_debug::mandos_go("mandos/reward_address.scen.json");
+}
+
 #[test]
 fn rewards_for_unstaked_go_to_the_owner_go() {
     elrond_wasm_debug::mandos_go("mandos/rewards_for_unStaked_go_to_the_owner.scen.json");
 }
 
+#[test]
+fn rewards_frozen_go() {
+    elrond_wasm_debug::mandos_go("mandos/rewards_frozen.scen.json");
+}
+
+#[test]
+fn rotate_bls_key_go() {
+    elrond_wasm_debug::mandos_go("mandos/rotate_bls_key.scen.json");
+}
+
+#[test]
+fn set_auction_contract_address_go() {
+    elrond_wasm_debug::mandos_go("mandos/set_auction_contract_address.scen.json");
+}
+
 #[test]
 fn set_num_blocks_before_unbond_go() {
     elrond_wasm_debug::mandos_go("mandos/set_num_blocks_before_unbond.scen.json");
 }
 
+#[test]
+fn stake_all_available_go() {
+    elrond_wasm_debug::mandos_go("mandos/stake_all_available.scen.json");
+}
+
+#[test]
+fn stake_already_staked_go() {
+    elrond_wasm_debug::mandos_go("mandos/stake_already_staked.scen.json");
+}
+
+#[test]
+fn stake_failure_circuit_breaker_go() {
+    elrond_wasm_debug::mandos_go("mandos/stake_failure_circuit_breaker.scen.json");
+}
+
+#[test]
+fn stake_mixed_statuses_go() {
+    elrond_wasm_debug::mandos_go("mandos/stake_mixed_statuses.scen.json");
+}
+
+#[test]
+fn stake_up_to_go() {
+    elrond_wasm_debug::mandos_go("mandos/stake_up_to.scen.json");
+}
+
 #[test]
 fn staking_1_go() {
     elrond_wasm_debug::mandos_go("mandos/staking_1.scen.json");
@@ -68,6 +258,16 @@ fn staking_2_go() {
     elrond_wasm_debug::mandos_go("mandos/staking_2.scen.json");
 }
 
+#[test]
+fn sweep_unaccounted_funds_go() {
+    elrond_wasm_debug::mandos_go("mandos/sweep_unaccounted_funds.scen.json");
+}
+
+#[test]
+fn sync_node_state_go() {
+    elrond_wasm_debug::mandos_go("mandos/sync_node_state.scen.json");
+}
+
 #[test]
 fn total_funds_getters_go() {
     elrond_wasm_debug::mandos_go("mandos/total_funds_getters.scen.json");
@@ -83,6 +283,11 @@ fn unbond_from_waiting_go() {
     elrond_wasm_debug::mandos_go("mandos/unbond_from_waiting.scen.json");
 }
 
+#[test]
+fn unbond_some_possible_nodes_go() {
+    elrond_wasm_debug::mandos_go("mandos/unbond_some_possible_nodes.scen.json");
+}
+
 #[test]
 fn unjail_go() {
     elrond_wasm_debug::mandos_go("mandos/unjail.scen.json");
@@ -113,6 +318,21 @@ fn unstake_5_backwards_go() {
     elrond_wasm_debug::mandos_go("mandos/unstake_5_backwards.scen.json");
 }
 
+#[test]
+fn unstake_node_order_go() {
+    elrond_wasm_debug::mandos_go("mandos/unstake_node_order.scen.json");
+}
+
+#[test]
+fn unstake_nodes_by_ids_go() {
+    elrond_wasm_debug::mandos_go("mandos/unstake_nodes_by_ids.scen.json");
+}
+
+#[test]
+fn unstake_tokens_amount_go() {
+    elrond_wasm_debug::mandos_go("mandos/unstake_tokens_amount.scen.json");
+}
+
 #[test]
 fn user_fund_getters_go() {
     elrond_wasm_debug::mandos_go("mandos/user_fund_getters.scen.json");
@@ -122,3 +342,13 @@ fn user_fund_getters_go() {
 fn version_go() {
     elrond_wasm_debug::mandos_go("mandos/version.scen.json");
 }
+
+#[test]
+fn withdraw_waiting_go() {
+    elrond_wasm_debug::mandos_go("mandos/withdraw_waiting.scen.json");
+}
+
+#[test]
+fn zero_nodes_go() {
+    elrond_wasm_debug::mandos_go("mandos/zero_nodes.scen.json");
+}
@@ -23,16 +23,49 @@ fn activate_nodes_rs() {
     elrond_wasm_debug::mandos_rs("mandos/activate_nodes.scen.json", &contract_map());
 }
 
+#[test]
+fn cancel_pending_activation_rs() {
+    elrond_wasm_debug::mandos_rs(
+        "mandos/cancel_pending_activation.scen.json",
+        &contract_map(),
+    );
+}
+
+#[test]
+fn cancel_unstake_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/cancel_unstake.scen.json", &contract_map());
+}
+
 #[test]
 fn change_service_fee_rs() {
     elrond_wasm_debug::mandos_rs("mandos/change_service_fee.scen.json", &contract_map());
 }
 
+#[test]
+fn claim_deferred_before_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/claim_deferred_before.scen.json", &contract_map());
+}
+
+#[test]
+fn claim_failed_stake_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/claim_failed_stake.scen.json", &contract_map());
+}
+
 #[test]
 fn claim_rewards_1_rs() {
     elrond_wasm_debug::mandos_rs("mandos/claim_rewards_1.scen.json", &contract_map());
 }
 
+#[test]
+fn claim_rewards_amount_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/claim_rewards_amount.scen.json", &contract_map());
+}
+
+#[test]
+fn claim_rewards_for_users_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/claim_rewards_for_users.scen.json", &contract_map());
+}
+
 #[test]
 fn claim_rewards_owner_with_stake_rs() {
     elrond_wasm_debug::mandos_rs(
@@ -65,6 +98,11 @@ fn claim_rewards_with_stake_modifications_rs() {
     );
 }
 
+#[test]
+fn compact_user_funds_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/compact_user_funds.scen.json", &contract_map());
+}
+
 #[test]
 fn continue_global_operations_rs() {
     elrond_wasm_debug::mandos_rs(
@@ -81,11 +119,126 @@ fn decrease_cap_in_bootstrap_mode_rs() {
     );
 }
 
+#[test]
+fn emergency_pause_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/emergency_pause.scen.json", &contract_map());
+}
+
+#[test]
+fn end_bootstrap_mode_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/end_bootstrap_mode.scen.json", &contract_map());
+}
+
+#[test]
+fn estimated_apr_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/estimated_apr.scen.json", &contract_map());
+}
+
+#[test]
+fn fee_reserve_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/fee_reserve.scen.json", &contract_map());
+}
+
+#[test]
+fn force_unstake_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/force_unstake.scen.json", &contract_map());
+}
+
 #[test]
 fn increase_delegation_cap_rs() {
     elrond_wasm_debug::mandos_rs("mandos/increase_delegation_cap.scen.json", &contract_map());
 }
 
+#[test]
+fn max_delegation_cap_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/max_delegation_cap.scen.json", &contract_map());
+}
+
+#[test]
+fn max_nodes_per_tx_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/max_nodes_per_tx.scen.json", &contract_map());
+}
+
+#[test]
+fn min_delegation_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/min_delegation.scen.json", &contract_map());
+}
+
+#[test]
+fn multiple_auctions_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/multiple_auctions.scen.json", &contract_map());
+}
+
+#[test]
+fn node_groups_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/node_groups.scen.json", &contract_map());
+}
+
+#[test]
+fn node_history_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/node_history.scen.json", &contract_map());
+}
+
+#[test]
+fn node_metadata_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/node_metadata.scen.json", &contract_map());
+}
+
+#[test]
+fn node_reward_share_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/node_reward_share.scen.json", &contract_map());
+}
+
+#[test]
+fn node_stake_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/node_stake.scen.json", &contract_map());
+}
+
+#[test]
+fn operator_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/operator.scen.json", &contract_map());
+}
+
+#[test]
+fn owner_min_stake_share_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/owner_min_stake_share.scen.json", &contract_map());
+}
+
+#[test]
+fn owner_reserve_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/owner_reserve.scen.json", &contract_map());
+}
+
+#[test]
+fn pause_delegations_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/pause_delegations.scen.json", &contract_map());
+}
+
+#[test]
+fn proportional_unstake_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/proportional_unstake.scen.json", &contract_map());
+}
+
+#[test]
+fn purge_inactive_user_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/purge_inactive_user.scen.json", &contract_map());
+}
+
+#[test]
+fn restake_matured_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/restake_matured.scen.json", &contract_map());
+}
+
+#[test]
+fn restake_unbonded_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/restake_unbonded.scen.json", &contract_map());
+}
+
+#[test]
+fn reward_address_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/reward_address.scen.json", &contract_map());
+}
+
 #[test]
 fn rewards_for_unstaked_go_to_the_owner_rs() {
     elrond_wasm_debug::mandos_rs(
@@ -94,6 +247,24 @@ fn rewards_for_unstaked_go_to_the_owner_rs() {
     );
 }
 
+#[test]
+fn rewards_frozen_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/rewards_frozen.scen.json", &contract_map());
+}
+
+#[test]
+fn rotate_bls_key_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/rotate_bls_key.scen.json", &contract_map());
+}
+
+#[test]
+fn set_auction_contract_address_rs() {
+    elrond_wasm_debug::mandos_rs(
+        "mandos/set_auction_contract_address.scen.json",
+        &contract_map(),
+    );
+}
+
 #[test]
 fn set_num_blocks_before_unbond_rs() {
     elrond_wasm_debug::mandos_rs(
@@ -102,6 +273,34 @@ fn set_num_blocks_before_unbond_rs() {
     );
 }
 
+#[test]
+fn stake_all_available_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/stake_all_available.scen.json", &contract_map());
+}
+
+#[test]
+fn stake_already_staked_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/stake_already_staked.scen.json", &contract_map());
+}
+
+#[test]
+fn stake_failure_circuit_breaker_rs() {
+    elrond_wasm_debug::mandos_rs(
+        "mandos/stake_failure_circuit_breaker.scen.json",
+        &contract_map(),
+    );
+}
+
+#[test]
+fn stake_mixed_statuses_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/stake_mixed_statuses.scen.json", &contract_map());
+}
+
+#[test]
+fn stake_up_to_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/stake_up_to.scen.json", &contract_map());
+}
+
 #[test]
 fn staking_1_rs() {
     elrond_wasm_debug::mandos_rs("mandos/staking_1.scen.json", &contract_map());
@@ -112,6 +311,16 @@ fn staking_2_rs() {
     elrond_wasm_debug::mandos_rs("mandos/staking_2.scen.json", &contract_map());
 }
 
+#[test]
+fn sweep_unaccounted_funds_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/sweep_unaccounted_funds.scen.json", &contract_map());
+}
+
+#[test]
+fn sync_node_state_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/sync_node_state.scen.json", &contract_map());
+}
+
 #[test]
 fn total_funds_getters_rs() {
     elrond_wasm_debug::mandos_rs("mandos/total_funds_getters.scen.json", &contract_map());
@@ -127,6 +336,14 @@ fn unbond_from_waiting_rs() {
     elrond_wasm_debug::mandos_rs("mandos/unbond_from_waiting.scen.json", &contract_map());
 }
 
+#[test]
+fn unbond_some_possible_nodes_rs() {
+    elrond_wasm_debug::mandos_rs(
+        "mandos/unbond_some_possible_nodes.scen.json",
+        &contract_map(),
+    );
+}
+
 #[test]
 fn unjail_rs() {
     elrond_wasm_debug::mandos_rs("mandos/unjail.scen.json", &contract_map());
@@ -157,6 +374,21 @@ fn unstake_5_backwards_rs() {
     elrond_wasm_debug::mandos_rs("mandos/unstake_5_backwards.scen.json", &contract_map());
 }
 
+#[test]
+fn unstake_node_order_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/unstake_node_order.scen.json", &contract_map());
+}
+
+#[test]
+fn unstake_nodes_by_ids_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/unstake_nodes_by_ids.scen.json", &contract_map());
+}
+
+#[test]
+fn unstake_tokens_amount_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/unstake_tokens_amount.scen.json", &contract_map());
+}
+
 #[test]
 fn user_fund_getters_rs() {
     elrond_wasm_debug::mandos_rs("mandos/user_fund_getters.scen.json", &contract_map());
@@ -166,3 +398,13 @@ fn user_fund_getters_rs() {
 fn version_rs() {
     elrond_wasm_debug::mandos_rs("mandos/version.scen.json", &contract_map());
 }
+
+#[test]
+fn withdraw_waiting_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/withdraw_waiting.scen.json", &contract_map());
+}
+
+#[test]
+fn zero_nodes_rs() {
+    elrond_wasm_debug::mandos_rs("mandos/zero_nodes.scen.json", &contract_map());
+}
@@ -23,6 +23,7 @@ pub trait DelegationUpdate:
     + delegation_latest::rewards_endpoints::RewardEndpointsModule
     + delegation_latest::user_stake_endpoints::UserStakeEndpointsModule
     + delegation_latest::user_stake_dust_cleanup::UserStakeDustCleanupModule
+    + delegation_latest::owner_reserve::OwnerReserveModule
     + delegation_latest::elrond_wasm_module_dns::DnsModule
     + delegation_latest::elrond_wasm_module_features::FeaturesModule
     + delegation_latest::elrond_wasm_module_pause::PauseModule
@@ -34,6 +35,14 @@ pub trait DelegationUpdate:
         env!("CARGO_PKG_VERSION")
     }
 
+    /// The version, followed by capability flags, so that tooling does not need to probe endpoints:
+    /// supports `unStakeNodesAndTokens`, supports bootstrap mode, supports checkpoint resets.
+    /// The 0.4 contracts have neither this view nor any of these capabilities.
+    #[view(getContractVersion)]
+    fn get_contract_version(&self) -> MultiResult4<&'static str, bool, bool, bool> {
+        (env!("CARGO_PKG_VERSION"), true, true, true).into()
+    }
+
     // INIT - update from genesis version
 
     /// the genesis contract didn't have the concept of total delegation cap
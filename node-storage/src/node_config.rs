@@ -1,4 +1,7 @@
-use crate::types::{BLSKey, BLSSignature, BLSStatusMultiArg, NodeState};
+use crate::types::{
+    decode_node_state_history, push_node_state_history_entry, BLSKey, BLSSignature,
+    BLSStatusMultiArg, NodeStakeCheck, NodeState, STAKE_STATUS_ALREADY_STAKED,
+};
 
 elrond_wasm::imports!();
 
@@ -7,6 +10,9 @@ elrond_wasm::imports!();
 /// Percents + 2 decimals -> 10000.
 // pub static PERCENTAGE_DENOMINATOR: usize = 10000;
 
+/// Bounds the storage used by `setNodeMetadata`.
+pub const MAX_NODE_METADATA_LENGTH: usize = 256;
+
 /// This module manages the validator node info:
 /// - how many nodes there are,
 /// - what BLS keys they have.
@@ -27,6 +33,16 @@ pub trait NodeConfigModule {
     #[storage_get("node_bls_to_id")]
     fn get_node_id(&self, bls_key: &BLSKey) -> usize;
 
+    /// Batch version of `getNodeId`, yields 0 for unknown BLS keys.
+    #[view(getNodeIds)]
+    fn get_node_ids(&self, #[var_args] bls_keys: VarArgs<BLSKey>) -> MultiResultVec<usize> {
+        bls_keys
+            .iter()
+            .map(|bls_key| self.get_node_id(bls_key))
+            .collect::<Vec<usize>>()
+            .into()
+    }
+
     #[storage_set("node_bls_to_id")]
     fn set_node_bls_to_id(&self, bls_key: &BLSKey, node_id: usize);
 
@@ -36,16 +52,27 @@ pub trait NodeConfigModule {
     #[storage_set("node_id_to_bls")]
     fn set_node_id_to_bls(&self, node_id: usize, bls_key: &BLSKey);
 
+    fn get_bls_keys_of_nodes(&self, node_ids: &[usize]) -> Vec<BLSKey> {
+        node_ids
+            .iter()
+            .map(|&node_id| self.get_node_id_to_bls(node_id))
+            .collect()
+    }
+
     #[storage_get("node_signature")]
     fn get_node_signature(&self, node_id: usize) -> BLSSignature;
 
     #[storage_set("node_signature")]
     fn set_node_signature(&self, node_id: usize, node_signature: BLSSignature);
 
+    #[storage_clear("node_signature")]
+    fn clear_node_signature(&self, node_id: usize);
+
+    /// Removed nodes no longer have a signature.
     #[view(getNodeSignature)]
     fn get_node_signature_endpoint(&self, bls_key: BLSKey) -> OptionalResult<BLSSignature> {
         let node_id = self.get_node_id(&bls_key);
-        if node_id == 0 {
+        if node_id == 0 || self.get_node_state(node_id) == NodeState::Removed {
             OptionalResult::None
         } else {
             OptionalResult::Some(self.get_node_signature(node_id))
@@ -57,7 +84,46 @@ pub trait NodeConfigModule {
     fn get_node_state(&self, node_id: usize) -> NodeState;
 
     #[storage_set("node_state")]
-    fn set_node_state(&self, node_id: usize, node_state: NodeState);
+    fn store_node_state(&self, node_id: usize, node_state: NodeState);
+
+    /// Also records the transition in the node state history.
+    fn set_node_state(&self, node_id: usize, node_state: NodeState) {
+        let old_state = self.get_node_state(node_id);
+        let mut history = self.get_node_state_history_bytes(node_id);
+        push_node_state_history_entry(
+            &mut history,
+            self.blockchain().get_block_nonce(),
+            old_state.discriminant(),
+            node_state.discriminant(),
+        );
+        self.set_node_state_history_bytes(node_id, &history);
+        self.store_node_state(node_id, node_state);
+    }
+
+    /// The last state transitions of each node, packed, see `node_state_history`.
+    #[storage_get("node_state_hist")]
+    fn get_node_state_history_bytes(&self, node_id: usize) -> Vec<u8>;
+
+    #[storage_set("node_state_hist")]
+    fn set_node_state_history_bytes(&self, node_id: usize, history: &[u8]);
+
+    /// The last state transitions of a node, oldest first:
+    /// block nonce, discriminant of the old state, discriminant of the new state (see `getNodeStatus`).
+    /// Adding a node is recorded as a transition from `Inactive` to `Inactive`.
+    /// Empty for unknown BLS keys.
+    #[view(getNodeStateHistory)]
+    fn get_node_state_history(&self, bls_key: BLSKey) -> MultiResultVec<MultiResult3<u64, u8, u8>> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            return Vec::<MultiResult3<u64, u8, u8>>::new().into();
+        }
+
+        decode_node_state_history(self.get_node_state_history_bytes(node_id).as_slice())
+            .into_iter()
+            .map(|entry| entry.into())
+            .collect::<Vec<MultiResult3<u64, u8, u8>>>()
+            .into()
+    }
 
     #[view(getNodeState)]
     fn get_node_state_endpoint(&self, bls_key: BLSKey) -> NodeState {
@@ -69,19 +135,346 @@ pub trait NodeConfigModule {
         }
     }
 
+    /// Decode-friendly version of the node state: the discriminant, followed by the block nonce
+    /// carried by the `UnBondPeriod` and `PendingUnBond` states (0 for the other states).
+    /// Discriminants: 0 = Inactive, 1 = PendingActivation, 2 = ActivationFailed, 3 = Active,
+    /// 4 = PendingDeactivation, 5 = UnBondPeriod, 6 = PendingUnBond, 7 = Removed.
+    /// Unknown node ids are reported as removed.
+    #[view(getNodeStatus)]
+    fn get_node_status(&self, node_id: usize) -> MultiResult2<u8, u64> {
+        let node_state = if node_id == 0 || node_id > self.num_nodes().get() {
+            NodeState::Removed
+        } else {
+            self.get_node_state(node_id)
+        };
+        (node_state.discriminant(), node_state.block_nonce()).into()
+    }
+
+    /// Per-key checks of `stakeNodes`: the BLS key is known and the node is inactive,
+    /// so keys that would make the whole batch fail can be filtered out beforehand.
+    /// Contract-wide conditions, such as the emergency pause, a staking halt, the stake per node
+    /// or the amount to stake, are not checked.
+    #[view(canStakeNode)]
+    fn can_stake_node(&self, bls_key: &BLSKey) -> NodeStakeCheck {
+        let node_id = self.get_node_id(bls_key);
+        if node_id == 0 {
+            NodeStakeCheck::UnknownNode
+        } else if self.get_node_state(node_id) != NodeState::Inactive {
+            NodeStakeCheck::NotInactive
+        } else {
+            NodeStakeCheck::Ok
+        }
+    }
+
+    /// Batch version of `canStakeNode`, yields one result per BLS key, in order.
+    #[view(canStakeNodes)]
+    fn can_stake_nodes(
+        &self,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> MultiResultVec<NodeStakeCheck> {
+        bls_keys
+            .iter()
+            .map(|bls_key| self.can_stake_node(bls_key))
+            .collect::<Vec<NodeStakeCheck>>()
+            .into()
+    }
+
+    /// Block nonce of the last settled state change of the node.
+    /// Pending states and reverts after failed auction calls do not count.
+    #[storage_get("node_state_nonce")]
+    fn get_node_state_change_nonce(&self, node_id: usize) -> u64;
+
+    #[storage_set("node_state_nonce")]
+    fn set_node_state_change_nonce(&self, node_id: usize, bl_nonce: u64);
+
+    fn record_node_state_change(&self, node_id: usize) {
+        self.set_node_state_change_nonce(node_id, self.blockchain().get_block_nonce());
+    }
+
+    #[view(getNodeStateChangeNonce)]
+    fn get_node_state_change_nonce_endpoint(&self, bls_key: BLSKey) -> OptionalResult<u64> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            OptionalResult::Some(self.get_node_state_change_nonce(node_id))
+        }
+    }
+
+    /// Stake required by the auction contract for the node, if set when the node was added.
+    /// Nodes added without an explicit stake use the global stake per node.
+    #[storage_get("node_stake")]
+    fn get_node_stake(&self, node_id: usize) -> Self::BigUint;
+
+    #[storage_set("node_stake")]
+    fn set_node_stake(&self, node_id: usize, node_stake: &Self::BigUint);
+
+    #[storage_is_empty("node_stake")]
+    fn is_empty_node_stake(&self, node_id: usize) -> bool;
+
+    #[storage_clear("node_stake")]
+    fn clear_node_stake(&self, node_id: usize);
+
+    fn get_node_stake_or_default(
+        &self,
+        node_id: usize,
+        default_stake: &Self::BigUint,
+    ) -> Self::BigUint {
+        if self.is_empty_node_stake(node_id) {
+            default_stake.clone()
+        } else {
+            self.get_node_stake(node_id)
+        }
+    }
+
+    /// Total stake required by the auction contract for the given nodes.
+    fn get_nodes_stake(&self, node_ids: &[usize], default_stake: &Self::BigUint) -> Self::BigUint {
+        let mut total = Self::BigUint::zero();
+        for &node_id in node_ids.iter() {
+            total += &self.get_node_stake_or_default(node_id, default_stake);
+        }
+        total
+    }
+
+    /// Stake sent to the auction contract for the node, recorded when the stake is confirmed.
+    /// Cleared when the node is unbonded.
+    #[view(getNodeStakedInAuction)]
+    #[storage_get("node_staked_in_auction")]
+    fn get_node_staked_in_auction(&self, node_id: usize) -> Self::BigUint;
+
+    #[storage_set("node_staked_in_auction")]
+    fn set_node_staked_in_auction(&self, node_id: usize, staked: &Self::BigUint);
+
+    #[storage_clear("node_staked_in_auction")]
+    fn clear_node_staked_in_auction(&self, node_id: usize);
+
+    /// Total stake of the nodes confirmed by the auction contract and not yet unbonded.
+    /// Stake of nodes rejected by the auction contract is not included.
+    #[view(getStakedInAuction)]
+    #[storage_get("staked_in_auction")]
+    fn get_staked_in_auction(&self) -> Self::BigUint;
+
+    #[storage_set("staked_in_auction")]
+    fn set_staked_in_auction(&self, staked_in_auction: &Self::BigUint);
+
+    /// Tokens unstaked from the auction contract via `unStakeTokensAmount`, not yet unbonded.
+    /// They back UnStaked funds, but are no longer counted in `getStakedInAuction`.
+    #[view(getUnStakedTokens)]
+    #[storage_get("unstaked_tokens")]
+    fn get_unstaked_tokens(&self) -> Self::BigUint;
+
+    #[storage_set("unstaked_tokens")]
+    fn set_unstaked_tokens(&self, unstaked_tokens: &Self::BigUint);
+
+    /// Block nonce when tokens were last unstaked via `unStakeTokensAmount`.
+    #[storage_get("unstaked_tokens_nonce")]
+    fn get_unstaked_tokens_nonce(&self) -> u64;
+
+    #[storage_set("unstaked_tokens_nonce")]
+    fn set_unstaked_tokens_nonce(&self, bl_nonce: u64);
+
+    /// Block nonce when the node was last sent to the auction contract for staking.
+    #[storage_get("node_pend_act_nonce")]
+    fn get_node_pending_activation_nonce(&self, node_id: usize) -> u64;
+
+    #[storage_set("node_pend_act_nonce")]
+    fn set_node_pending_activation_nonce(&self, node_id: usize, bl_nonce: u64);
+
+    /// How many times the node was unstaked.
+    #[storage_get("node_unstake_count")]
+    fn get_node_unstake_count(&self, node_id: usize) -> usize;
+
+    #[storage_set("node_unstake_count")]
+    fn set_node_unstake_count(&self, node_id: usize, unstake_count: usize);
+
+    #[view(getNodeUnstakeCount)]
+    fn get_node_unstake_count_endpoint(&self, bls_key: BLSKey) -> OptionalResult<usize> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            OptionalResult::Some(self.get_node_unstake_count(node_id))
+        }
+    }
+
+    /// Index of the auction contract the node is staked with.
+    /// 0 is the main auction contract.
+    #[storage_get("node_auction_idx")]
+    fn get_node_auction_index(&self, node_id: usize) -> usize;
+
+    #[storage_set("node_auction_idx")]
+    fn set_node_auction_index(&self, node_id: usize, auction_index: usize);
+
+    #[view(getNodeAuctionIndex)]
+    fn get_node_auction_index_endpoint(&self, bls_key: BLSKey) -> OptionalResult<usize> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            OptionalResult::Some(self.get_node_auction_index(node_id))
+        }
+    }
+
+    /// Optional label set by the owner, so that nodes can be staked/unstaked by group.
+    /// 0 means no group.
+    #[storage_get("node_group")]
+    fn get_node_group(&self, node_id: usize) -> u32;
+
+    #[storage_set("node_group")]
+    fn set_node_group(&self, node_id: usize, group_id: u32);
+
+    #[endpoint(setNodeGroup)]
+    fn set_node_group_endpoint(
+        &self,
+        group_id: u32,
+        #[var_args] bls_keys: VarArgs<BLSKey>,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner can set node group");
+
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(bls_key);
+            require!(node_id != 0, "unknown node provided");
+            self.set_node_group(node_id, group_id);
+        }
+
+        Ok(())
+    }
+
+    /// Ids of the nodes in a group, in id order. Removed nodes are skipped.
+    fn get_node_ids_of_group(&self, group_id: u32) -> Vec<usize> {
+        let num_nodes = self.num_nodes().get();
+        (1..=num_nodes)
+            .filter(|&node_id| {
+                self.get_node_group(node_id) == group_id
+                    && self.get_node_state(node_id) != NodeState::Removed
+            })
+            .collect()
+    }
+
+    #[view(getNodesByGroup)]
+    fn get_nodes_by_group(&self, group_id: u32) -> MultiResultVec<BLSKey> {
+        let node_ids = self.get_node_ids_of_group(group_id);
+        self.get_bls_keys_of_nodes(&node_ids).into()
+    }
+
+    /// Nodes that are earning rewards, i.e. currently staked in the auction contract.
+    fn is_node_rewarded(&self, node_id: usize) -> bool {
+        matches!(
+            self.get_node_state(node_id),
+            NodeState::Active | NodeState::PendingDeactivation
+        )
+    }
+
+    fn count_rewarded_nodes(&self) -> usize {
+        let num_nodes = self.num_nodes().get();
+        (1..=num_nodes)
+            .filter(|&node_id| self.is_node_rewarded(node_id))
+            .count()
+    }
+
+    /// Block nonce when the node last became active.
+    /// It is overwritten each time the node is staked again after being unbonded,
+    /// so it always marks the start of the current (or last) activity period.
+    #[storage_get("node_activ_nonce")]
+    fn get_node_activation_nonce(&self, node_id: usize) -> u64;
+
+    #[storage_set("node_activ_nonce")]
+    fn set_node_activation_nonce(&self, node_id: usize, bl_nonce: u64);
+
+    /// Yields 0 for nodes that were never active.
+    #[view(getNodeActivationNonce)]
+    fn get_node_activation_nonce_endpoint(&self, bls_key: BLSKey) -> OptionalResult<u64> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            OptionalResult::Some(self.get_node_activation_nonce(node_id))
+        }
+    }
+
+    /// The per-node reward index at the moment the node last became active.
+    /// The difference between the current index and this value is what the node earned since.
+    #[storage_get("node_rew_checkp")]
+    fn get_node_reward_checkpoint(&self, node_id: usize) -> Self::BigUint;
+
+    #[storage_set("node_rew_checkp")]
+    fn set_node_reward_checkpoint(&self, node_id: usize, reward_index: &Self::BigUint);
+
+    /// Informational only, e.g. operator name and website, for explorers to display.
+    /// Not used by any node operation.
+    #[storage_get("node_metadata")]
+    fn get_node_metadata(&self, node_id: usize) -> BoxedBytes;
+
+    #[storage_set("node_metadata")]
+    fn set_node_metadata(&self, node_id: usize, metadata: &BoxedBytes);
+
+    #[storage_clear("node_metadata")]
+    fn clear_node_metadata(&self, node_id: usize);
+
+    /// Empty metadata clears it.
+    #[endpoint(setNodeMetadata)]
+    fn set_node_metadata_endpoint(&self, bls_key: BLSKey, metadata: BoxedBytes) -> SCResult<()> {
+        only_owner!(self, "only owner can set node metadata");
+
+        let node_id = self.get_node_id(&bls_key);
+        require!(node_id != 0, "unknown node provided");
+        require!(
+            metadata.len() <= MAX_NODE_METADATA_LENGTH,
+            "node metadata too long"
+        );
+
+        if metadata.is_empty() {
+            self.clear_node_metadata(node_id);
+        } else {
+            self.set_node_metadata(node_id, &metadata);
+        }
+
+        Ok(())
+    }
+
+    /// Empty if not set.
+    #[view(getNodeMetadata)]
+    fn get_node_metadata_endpoint(&self, bls_key: BLSKey) -> OptionalResult<BoxedBytes> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalResult::None
+        } else {
+            OptionalResult::Some(self.get_node_metadata(node_id))
+        }
+    }
+
+    /// Lists all nodes, with their BLS keys and full state.
+    /// The unbond nonces of `UnBondPeriod`/`PendingUnBond` are included in the encoded state.
     #[view(getAllNodeStates)]
-    fn get_all_node_states(&self) -> MultiResultVec<MultiResult2<BLSKey, u8>> {
+    fn get_all_node_states(&self) -> MultiResultVec<MultiResult2<BLSKey, NodeState>> {
         let num_nodes = self.num_nodes().get();
         let mut result = Vec::new();
-        for i in 1..num_nodes + 1 {
+        for i in 1..=num_nodes {
             result.push(MultiResult2::from((
                 self.get_node_id_to_bls(i),
-                self.get_node_state(i).discriminant(),
+                self.get_node_state(i),
             )));
         }
         result.into()
     }
 
+    /// Number of nodes in each state, keyed by the state discriminant, see `getNodeStatus`.
+    /// All states are listed, in discriminant order, including those without nodes.
+    #[view(getNodeStateCounts)]
+    fn get_node_state_counts(&self) -> MultiResultVec<MultiResult2<u8, usize>> {
+        let mut counts = [0usize; NodeState::NUM_DISCRIMINANTS];
+        let num_nodes = self.num_nodes().get();
+        for node_id in 1..=num_nodes {
+            counts[self.get_node_state(node_id).discriminant() as usize] += 1;
+        }
+
+        let mut result = Vec::with_capacity(NodeState::NUM_DISCRIMINANTS);
+        for (discriminant, &count) in counts.iter().enumerate() {
+            result.push(MultiResult2::from((discriminant as u8, count)));
+        }
+        result.into()
+    }
+
     #[view(getNodeBlockNonceOfUnstake)]
     fn get_node_bl_nonce_of_unstake_endpoint(&self, bls_key: BLSKey) -> OptionalResult<u64> {
         let node_id = self.get_node_id(&bls_key);
@@ -94,16 +487,57 @@ pub trait NodeConfigModule {
         }
     }
 
+    /// Keys and signatures are received as raw bytes, so that their length can be validated with a clear error.
+    fn validate_bls_key(&self, bls_key_bytes: &BoxedBytes) -> SCResult<BLSKey> {
+        match BLSKey::from_slice(bls_key_bytes.as_slice()) {
+            Some(bls_key) => Ok(bls_key),
+            None => sc_error!("wrong BLS key length"),
+        }
+    }
+
+    fn validate_bls_signature(&self, bls_sig_bytes: &BoxedBytes) -> SCResult<BLSSignature> {
+        match BLSSignature::from_slice(bls_sig_bytes.as_slice()) {
+            Some(bls_sig) => Ok(bls_sig),
+            None => sc_error!("wrong BLS signature length"),
+        }
+    }
+
+    /// Yields the ids of the added nodes, in argument order.
+    /// Keys of removed nodes get their old id back.
+    /// The whole batch fails if any of the keys is already registered, including twice in the same batch.
     #[endpoint(addNodes)]
     fn add_nodes(
         &self,
-        #[var_args] bls_keys_signatures: VarArgs<MultiArg2<BLSKey, BLSSignature>>,
-    ) -> SCResult<()> {
+        #[var_args] bls_keys_signatures: VarArgs<MultiArg2<BoxedBytes, BoxedBytes>>,
+    ) -> SCResult<MultiResultVec<usize>> {
         only_owner!(self, "only owner can add nodes");
+        self.perform_add_nodes(bls_keys_signatures, None)
+    }
 
+    /// Same as `addNodes`, but the nodes require a different stake than the global stake per node,
+    /// e.g. because the protocol requirement changed since the other nodes were added.
+    #[endpoint(addNodesWithStake)]
+    fn add_nodes_with_stake(
+        &self,
+        node_stake: Self::BigUint,
+        #[var_args] bls_keys_signatures: VarArgs<MultiArg2<BoxedBytes, BoxedBytes>>,
+    ) -> SCResult<MultiResultVec<usize>> {
+        only_owner!(self, "only owner can add nodes");
+        require!(node_stake > 0, "node stake cannot be zero");
+        self.perform_add_nodes(bls_keys_signatures, Some(&node_stake))
+    }
+
+    fn perform_add_nodes(
+        &self,
+        bls_keys_signatures: VarArgs<MultiArg2<BoxedBytes, BoxedBytes>>,
+        opt_node_stake: Option<&Self::BigUint>,
+    ) -> SCResult<MultiResultVec<usize>> {
         let mut num_nodes = self.num_nodes().get();
+        let mut node_ids = Vec::new();
         for bls_sig_pair_arg in bls_keys_signatures.into_vec().into_iter() {
-            let (bls_key, bls_sig) = bls_sig_pair_arg.into_tuple();
+            let (bls_key_bytes, bls_sig_bytes) = bls_sig_pair_arg.into_tuple();
+            let bls_key = self.validate_bls_key(&bls_key_bytes)?;
+            let bls_sig = self.validate_bls_signature(&bls_sig_bytes)?;
             let mut node_id = self.get_node_id(&bls_key);
             if node_id == 0 {
                 num_nodes += 1;
@@ -112,17 +546,32 @@ pub trait NodeConfigModule {
                 self.set_node_id_to_bls(node_id, &bls_key);
                 self.set_node_state(node_id, NodeState::Inactive);
                 self.set_node_signature(node_id, bls_sig);
+                self.record_node_state_change(node_id);
             } else if self.get_node_state(node_id) == NodeState::Removed {
                 self.set_node_state(node_id, NodeState::Inactive);
                 self.set_node_signature(node_id, bls_sig);
+                self.record_node_state_change(node_id);
             } else {
                 return sc_error!("node already registered");
             }
+
+            if let Some(node_stake) = opt_node_stake {
+                self.set_node_stake(node_id, node_stake);
+            } else {
+                self.clear_node_stake(node_id);
+            }
+
+            node_ids.push(node_id);
         }
         self.num_nodes().set(&num_nodes);
-        Ok(())
+        Ok(node_ids.into())
     }
 
+    /// Removed node ids are tombstoned, not reused: they stay in `Removed` state,
+    /// which all node scans skip, and keep their BLS key mapping
+    /// so that re-adding the same key revives the same node id.
+    /// The signature is deleted, a new one is provided when the node is added again.
+    /// The whole transaction fails if any of the nodes is not inactive.
     #[endpoint(removeNodes)]
     fn remove_nodes(&self, #[var_args] bls_keys: VarArgs<BLSKey>) -> SCResult<()> {
         only_owner!(self, "only owner can remove nodes");
@@ -135,29 +584,88 @@ pub trait NodeConfigModule {
                 "only inactive nodes can be removed"
             );
             self.set_node_state(node_id, NodeState::Removed);
+            self.clear_node_signature(node_id);
+            self.record_node_state_change(node_id);
         }
 
         Ok(())
     }
 
+    /// Replaces the BLS key and signature of a node, keeping its node id.
+    /// Only allowed while the node is inactive.
+    #[endpoint(rotateBlsKey)]
+    fn rotate_bls_key(
+        &self,
+        old_key: BLSKey,
+        new_key_bytes: BoxedBytes,
+        new_signature_bytes: BoxedBytes,
+    ) -> SCResult<()> {
+        only_owner!(self, "only owner can rotate BLS keys");
+
+        let new_key = self.validate_bls_key(&new_key_bytes)?;
+        let new_signature = self.validate_bls_signature(&new_signature_bytes)?;
+
+        let node_id = self.get_node_id(&old_key);
+        require!(node_id != 0, "node not registered");
+        require!(
+            self.get_node_state(node_id) == NodeState::Inactive,
+            "only inactive nodes can have their BLS key rotated"
+        );
+        require!(
+            self.get_node_id(&new_key) == 0,
+            "new BLS key already registered"
+        );
+
+        self.set_node_bls_to_id(&old_key, 0);
+        self.set_node_bls_to_id(&new_key, node_id);
+        self.set_node_id_to_bls(node_id, &new_key);
+        self.set_node_signature(node_id, new_signature);
+
+        Ok(())
+    }
+
+    /// Keeps only the nodes in the expected state, each of them once.
+    /// Callbacks use it so that duplicate or stray node ids are skipped instead of failing the whole batch.
+    fn filter_nodes_by_state<F: Fn(NodeState) -> bool>(
+        &self,
+        node_ids: Vec<usize>,
+        expected_state: F,
+    ) -> Vec<usize> {
+        let mut result = Vec::with_capacity(node_ids.len());
+        for node_id in node_ids.into_iter() {
+            if !result.contains(&node_id) && expected_state(self.get_node_state(node_id)) {
+                result.push(node_id);
+            }
+        }
+        result
+    }
+
+    /// Yields the ids of the nodes that succeeded,
+    /// the ids of the nodes the auction contract reported as already staked,
+    /// and the ids of the nodes that failed, each with the status code returned by the auction contract.
     fn split_node_ids_by_err(
         &self,
         mut node_ids: Vec<usize>,
         node_status_args: VarArgs<BLSStatusMultiArg>,
-    ) -> (Vec<usize>, Vec<usize>) {
-        let mut failed_node_ids: Vec<usize> = Vec::new();
+    ) -> (Vec<usize>, Vec<usize>, Vec<(usize, i32)>) {
+        let mut already_staked_node_ids: Vec<usize> = Vec::new();
+        let mut failed_node_statuses: Vec<(usize, i32)> = Vec::new();
         for arg in node_status_args.into_vec().into_iter() {
             let (bls_key, status) = arg.into_tuple();
             if status != 0 {
                 let node_id = self.get_node_id(&bls_key);
-                // move node from ok nodes to failed ones
+                // move node from ok nodes to already staked or failed ones
                 if let Some(pos) = node_ids.iter().position(|x| *x == node_id) {
                     node_ids.swap_remove(pos);
-                    failed_node_ids.push(node_id);
+                    if status == STAKE_STATUS_ALREADY_STAKED {
+                        already_staked_node_ids.push(node_id);
+                    } else {
+                        failed_node_statuses.push((node_id, status));
+                    }
                 }
             }
         }
 
-        (node_ids, failed_node_ids)
+        (node_ids, already_staked_node_ids, failed_node_statuses)
     }
 }
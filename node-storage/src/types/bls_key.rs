@@ -16,6 +16,16 @@ impl BLSKey {
     pub fn from_array(arr: [u8; BLS_KEY_BYTE_LENGTH]) -> Self {
         BLSKey(Box::new(arr))
     }
+
+    /// Yields None if the slice does not have exactly the BLS key length.
+    pub fn from_slice(slice: &[u8]) -> Option<Self> {
+        if slice.len() != BLS_KEY_BYTE_LENGTH {
+            return None;
+        }
+        let mut arr = [0u8; BLS_KEY_BYTE_LENGTH];
+        arr.copy_from_slice(slice);
+        Some(BLSKey::from_array(arr))
+    }
 }
 
 // only needed for tests
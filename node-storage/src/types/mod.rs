@@ -1,9 +1,22 @@
 pub mod bls_key;
 pub mod bls_sig;
+pub mod node_stake_check;
 pub mod node_state;
+pub mod node_state_history;
 
 pub use bls_key::BLSKey;
 pub use bls_sig::BLSSignature;
+pub use node_stake_check::NodeStakeCheck;
 pub use node_state::*;
+pub use node_state_history::*;
 
 pub type BLSStatusMultiArg = elrond_wasm::types::MultiArg2<BLSKey, i32>;
+
+/// Status code returned by the auction contract for a node that is already staked.
+///
+/// The auction contract answers `stake` with a `(BLS key, status)` pair for every key it did not stake,
+/// 0 meaning ok. Code 2 is returned for a key that is already registered there as staked.
+/// Such a node is live, so it is not treated as a failure when staking.
+/// The auction mock returns it via `setBlsDeliberateError`,
+/// see the `stake_already_staked` and `stake_mixed_statuses` mandos scenarios.
+pub const STAKE_STATUS_ALREADY_STAKED: i32 = 2;
@@ -0,0 +1,17 @@
+elrond_wasm::derive_imports!();
+
+/// Whether a node can be sent to the auction contract for staking,
+/// as checked by `stakeNodes` for each BLS key.
+#[derive(
+    TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, PartialEq, Clone, Copy, Debug,
+)]
+pub enum NodeStakeCheck {
+    /// Node is inactive and can be staked.
+    Ok,
+
+    /// BLS key is not registered in the contract.
+    UnknownNode,
+
+    /// Node is registered, but not inactive.
+    NotInactive,
+}
@@ -11,8 +11,8 @@ pub enum NodeState {
     /// Stake call to auction sent, but callback not yet received.
     PendingActivation,
 
-    /// Node stake was sent to the auction SC, but the transaction failed for the node.
-    /// No longer used.
+    /// Node stake was sent to the auction SC, but the auction SC rejected the node.
+    /// The stake stays in the auction SC until claimed back via `claimFailedStake`.
     ActivationFailed,
 
     /// Node is registered in the auction SC, active and producing rewards.
@@ -34,6 +34,9 @@ pub enum NodeState {
 }
 
 impl NodeState {
+    /// Number of distinct values yielded by `discriminant`.
+    pub const NUM_DISCRIMINANTS: usize = 8;
+
     pub fn discriminant(&self) -> u8 {
         match self {
             NodeState::Inactive => 0,
@@ -46,4 +49,13 @@ impl NodeState {
             NodeState::Removed => 7,
         }
     }
+
+    /// The block nonce carried by the state, 0 for states without one.
+    pub fn block_nonce(&self) -> u64 {
+        match self {
+            NodeState::UnBondPeriod { started } => *started,
+            NodeState::PendingUnBond { unbond_started } => *unbond_started,
+            _ => 0,
+        }
+    }
 }
@@ -0,0 +1,35 @@
+use elrond_wasm::Vec;
+
+/// Only the most recent state changes of a node are kept.
+pub const NODE_STATE_HISTORY_MAX_ENTRIES: usize = 16;
+
+/// Each entry is packed as: block nonce (8 bytes, big endian), old state discriminant, new state discriminant.
+pub const NODE_STATE_HISTORY_ENTRY_LENGTH: usize = 10;
+
+/// Appends an entry to the packed history, evicting the oldest entry if the history is full.
+pub fn push_node_state_history_entry(
+    history: &mut Vec<u8>,
+    bl_nonce: u64,
+    from_state: u8,
+    to_state: u8,
+) {
+    if history.len() >= NODE_STATE_HISTORY_MAX_ENTRIES * NODE_STATE_HISTORY_ENTRY_LENGTH {
+        history.drain(..NODE_STATE_HISTORY_ENTRY_LENGTH);
+    }
+    history.extend_from_slice(&bl_nonce.to_be_bytes()[..]);
+    history.push(from_state);
+    history.push(to_state);
+}
+
+/// Unpacks the history into (block nonce, old state discriminant, new state discriminant) entries, oldest first.
+pub fn decode_node_state_history(history: &[u8]) -> Vec<(u64, u8, u8)> {
+    history
+        .chunks_exact(NODE_STATE_HISTORY_ENTRY_LENGTH)
+        .map(|entry| {
+            let bl_nonce = entry[..8]
+                .iter()
+                .fold(0u64, |nonce, &byte| (nonce << 8) | byte as u64);
+            (bl_nonce, entry[8], entry[9])
+        })
+        .collect()
+}
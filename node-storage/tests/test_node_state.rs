@@ -0,0 +1,162 @@
+use node_storage::node_config::NodeConfigModule;
+use node_storage::types::bls_key::BLS_KEY_BYTE_LENGTH;
+use node_storage::types::{
+    decode_node_state_history, push_node_state_history_entry, BLSKey, NodeState,
+    NODE_STATE_HISTORY_ENTRY_LENGTH, NODE_STATE_HISTORY_MAX_ENTRIES, STAKE_STATUS_ALREADY_STAKED,
+};
+
+use elrond_wasm::types::{MultiArg2, VarArgs};
+use elrond_wasm_debug::TxContext;
+
+#[test]
+fn test_filter_nodes_by_state() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    module.set_node_state(1, NodeState::PendingDeactivation);
+    module.set_node_state(2, NodeState::UnBondPeriod { started: 5 });
+    module.set_node_state(3, NodeState::PendingDeactivation);
+
+    // node 2 is in the wrong state, node 3 appears twice
+    let node_ids = module.filter_nodes_by_state(vec![1, 2, 3, 3], |node_state| {
+        node_state == NodeState::PendingDeactivation
+    });
+    assert_eq!(node_ids, vec![1, 3]);
+
+    let node_ids = module.filter_nodes_by_state(vec![4], |node_state| {
+        node_state == NodeState::PendingDeactivation
+    });
+    assert!(node_ids.is_empty());
+}
+
+#[test]
+fn test_filter_nodes_by_state_pending_unbond() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    module.set_node_state(1, NodeState::PendingUnBond { unbond_started: 5 });
+    module.set_node_state(2, NodeState::Inactive);
+
+    let node_ids = module.filter_nodes_by_state(vec![2, 1], |node_state| {
+        matches!(node_state, NodeState::PendingUnBond { .. })
+    });
+    assert_eq!(node_ids, vec![1]);
+}
+
+#[test]
+fn test_split_node_ids_by_err() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    for node_id in 1..=3 {
+        let bls_key = BLSKey::from_array([node_id as u8; BLS_KEY_BYTE_LENGTH]);
+        module.set_node_bls_to_id(&bls_key, node_id);
+    }
+
+    // the auction contract only reports the failed nodes
+    let node_status_args = VarArgs::from(vec![
+        MultiArg2::from((BLSKey::from_array([2u8; BLS_KEY_BYTE_LENGTH]), 1i32)),
+        MultiArg2::from((BLSKey::from_array([3u8; BLS_KEY_BYTE_LENGTH]), 0i32)),
+    ]);
+    let (node_ids_ok, node_ids_already_staked, node_statuses_fail) =
+        module.split_node_ids_by_err(vec![1, 2, 3], node_status_args);
+    assert_eq!(node_ids_ok, vec![1, 3]);
+    assert!(node_ids_already_staked.is_empty());
+    assert_eq!(node_statuses_fail, vec![(2, 1)]);
+}
+
+#[test]
+fn test_split_node_ids_by_err_already_staked() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    for node_id in 1..=4 {
+        let bls_key = BLSKey::from_array([node_id as u8; BLS_KEY_BYTE_LENGTH]);
+        module.set_node_bls_to_id(&bls_key, node_id);
+    }
+
+    let node_status_args = VarArgs::from(vec![
+        MultiArg2::from((
+            BLSKey::from_array([2u8; BLS_KEY_BYTE_LENGTH]),
+            STAKE_STATUS_ALREADY_STAKED,
+        )),
+        MultiArg2::from((BLSKey::from_array([3u8; BLS_KEY_BYTE_LENGTH]), 1i32)),
+        MultiArg2::from((BLSKey::from_array([4u8; BLS_KEY_BYTE_LENGTH]), 0i32)),
+    ]);
+    let (node_ids_ok, node_ids_already_staked, node_statuses_fail) =
+        module.split_node_ids_by_err(vec![1, 2, 3, 4], node_status_args);
+    assert_eq!(node_ids_ok, vec![1, 4]);
+    assert_eq!(node_ids_already_staked, vec![2]);
+    assert_eq!(node_statuses_fail, vec![(3, 1)]);
+}
+
+#[test]
+fn test_get_node_status() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    module.num_nodes().set(&3);
+    module.set_node_state(1, NodeState::Active);
+    module.set_node_state(2, NodeState::UnBondPeriod { started: 5 });
+    module.set_node_state(3, NodeState::PendingUnBond { unbond_started: 7 });
+
+    assert_eq!(module.get_node_status(1).into_tuple(), (3, 0));
+    assert_eq!(module.get_node_status(2).into_tuple(), (5, 5));
+    assert_eq!(module.get_node_status(3).into_tuple(), (6, 7));
+
+    // unknown nodes
+    assert_eq!(module.get_node_status(0).into_tuple(), (7, 0));
+    assert_eq!(module.get_node_status(4).into_tuple(), (7, 0));
+}
+
+#[test]
+fn test_get_node_state_counts() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    module.num_nodes().set(&4);
+    module.set_node_state(1, NodeState::Active);
+    module.set_node_state(2, NodeState::UnBondPeriod { started: 5 });
+    module.set_node_state(3, NodeState::Active);
+    // node 4 left Inactive
+
+    let counts: Vec<(u8, usize)> = module
+        .get_node_state_counts()
+        .into_vec()
+        .into_iter()
+        .map(|count| count.into_tuple())
+        .collect();
+    assert_eq!(
+        counts,
+        [
+            (0, 1),
+            (1, 0),
+            (2, 0),
+            (3, 2),
+            (4, 0),
+            (5, 1),
+            (6, 0),
+            (7, 0)
+        ]
+    );
+}
+
+#[test]
+fn test_node_state_history() {
+    let module = node_storage::node_config::contract_obj(TxContext::dummy());
+
+    module.num_nodes().set(&1);
+    module.set_node_state(1, NodeState::PendingActivation);
+    module.set_node_state(1, NodeState::Active);
+
+    let history = decode_node_state_history(module.get_node_state_history_bytes(1).as_slice());
+    assert_eq!(history, vec![(0, 0, 1), (0, 1, 3)]);
+
+    // only the last entries are kept
+    let mut history_bytes = Vec::new();
+    for bl_nonce in 0..20u64 {
+        push_node_state_history_entry(&mut history_bytes, bl_nonce, 3, 4);
+    }
+    assert_eq!(
+        history_bytes.len(),
+        NODE_STATE_HISTORY_MAX_ENTRIES * NODE_STATE_HISTORY_ENTRY_LENGTH
+    );
+    let history = decode_node_state_history(history_bytes.as_slice());
+    assert_eq!(history.len(), NODE_STATE_HISTORY_MAX_ENTRIES);
+    assert_eq!(history[0], (4, 3, 4));
+    assert_eq!(history[NODE_STATE_HISTORY_MAX_ENTRIES - 1], (19, 3, 4));
+}
@@ -9,6 +9,14 @@ pub enum SwapDirection {
     Backwards,
 }
 
+/// Whether funds created at `created` can be claimed at `current_bl_nonce`.
+/// Never true if adding the waiting period overflows, whatever the period was configured to.
+pub fn is_claimable(created: u64, n_blocks_before_claim: u64, current_bl_nonce: u64) -> bool {
+    created
+        .checked_add(n_blocks_before_claim)
+        .map_or(false, |claim_nonce| current_bl_nonce >= claim_nonce)
+}
+
 /// Deals with storage data about delegators.
 #[elrond_wasm_derive::module]
 pub trait FundModule {
@@ -72,6 +80,27 @@ pub trait FundModule {
         sum
     }
 
+    /// Sums the funds of the type list that come before the first fund item of the user, in list order.
+    /// Yields `None` if the user has no funds of that type.
+    fn query_sum_funds_ahead_of_user(
+        &self,
+        fund_type: FundType,
+        user_id: usize,
+    ) -> Option<Self::BigUint> {
+        let mut sum = Self::BigUint::zero();
+        let type_list = self.get_fund_list_by_type(fund_type);
+        let mut id = type_list.first;
+        while id > 0 {
+            let fund_item = self.fund_by_id(id).get();
+            if fund_item.user_id == user_id {
+                return Some(sum);
+            }
+            sum += &fund_item.balance;
+            id = fund_item.type_list_next;
+        }
+        None
+    }
+
     fn query_sum_funds_by_user_type<F>(
         &self,
         user_id: usize,
@@ -179,6 +208,34 @@ pub trait FundModule {
         count
     }
 
+    /// All fund items of a user, one entry per item: fund type, balance and creation nonce.
+    /// The creation nonce is 0 for types that do not record one (e.g. `Active`).
+    /// Items are grouped by type, each group in list order. Meant for exporting user data.
+    #[view(getUserFundEntries)]
+    fn get_user_fund_entries(
+        &self,
+        user_id: usize,
+    ) -> MultiResultVec<MultiResult3<FundType, Self::BigUint, u64>> {
+        let mut result = Vec::<MultiResult3<FundType, Self::BigUint, u64>>::new();
+        for &fund_type in FundType::ALL_TYPES.iter() {
+            self.foreach_fund_by_user_type(
+                user_id,
+                fund_type,
+                SwapDirection::Forwards,
+                |fund_item| {
+                    let created = match fund_item.fund_desc {
+                        FundDescription::Waiting { created }
+                        | FundDescription::UnStaked { created }
+                        | FundDescription::DeferredPayment { created } => created,
+                        _ => 0,
+                    };
+                    result.push((fund_type, fund_item.balance, created).into());
+                },
+            );
+        }
+        result.into()
+    }
+
     /// Adds at the end of the fund by type list.
     fn add_fund_to_type_list(&self, id: usize, new_fund_item: &mut FundItem<Self::BigUint>) {
         self.fund_list_by_type(new_fund_item.fund_desc.fund_type())
@@ -568,6 +625,52 @@ pub trait FundModule {
         total_transformed
     }
 
+    /// Merges the fund items of a user that have identical descriptions into the earliest of them,
+    /// freeing the storage of the others. Balances do not change.
+    /// Items of types without extra data (e.g. `Active`) all merge together,
+    /// the others (e.g. `Waiting`, `UnStaked`) only merge if they have the same `created` nonce.
+    /// Returns the number of fund items removed.
+    fn compact_user_funds(&self, user_id: usize, fund_type: FundType) -> usize {
+        let mut kept_items: Vec<(FundDescription, usize)> = Vec::new();
+        let mut num_removed = 0usize;
+        let mut id = self.first_id_of_user_type(user_id, fund_type, SwapDirection::Forwards);
+
+        while id > 0 {
+            let mut fund_item = self.fund_by_id(id).get();
+            let next_id = fund_item.user_list_next; // save next id now, because fund_item can be destroyed
+
+            let opt_kept_id = kept_items
+                .iter()
+                .find(|(fund_desc, _)| *fund_desc == fund_item.fund_desc)
+                .map(|&(_, kept_id)| kept_id);
+            if let Some(kept_id) = opt_kept_id {
+                // extract
+                let balance = self.delete_fund(&mut fund_item);
+                self.fund_by_id(id).set(&fund_item);
+
+                // add to the kept item + synchronize sums
+                self.fund_by_id(kept_id).update(|kept_item| {
+                    kept_item.balance += &balance;
+                });
+                self.fund_list_by_type(fund_type).update(|type_list| {
+                    type_list.total_balance += &balance;
+                });
+                self.fund_list_by_user(user_id, fund_type)
+                    .update(|user_list| {
+                        user_list.total_balance += &balance;
+                    });
+
+                num_removed += 1;
+            } else {
+                kept_items.push((fund_item.fund_desc, id));
+            }
+
+            id = next_id;
+        }
+
+        num_removed
+    }
+
     fn destroy_all_for_user<I>(
         &self,
         user_id: usize,
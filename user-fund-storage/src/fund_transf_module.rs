@@ -1,12 +1,98 @@
 elrond_wasm::imports!();
 
 use crate::fund_module;
-use crate::fund_module::SwapDirection;
+use crate::fund_module::{is_claimable, SwapDirection};
 use crate::types::{FundDescription, FundItem, FundType};
 
 /// Deals with storage data about delegators.
 #[elrond_wasm_derive::module]
 pub trait FundTransformationsModule: fund_module::FundModule {
+    /// Emitted once per user affected by a transformation call, with the amount converted for that user.
+    /// Event ids are otherwise declared in the events module of the delegation contract, 0x10 is reserved there.
+    #[legacy_event("0x0000000000000000000000000000000000000000000000000000000000000010")]
+    fn fund_transformed_event(
+        &self,
+        user_id: usize,
+        from_type: FundType,
+        to_type: FundType,
+        amount: &Self::BigUint,
+    );
+
+    fn log_fund_transformation(
+        &self,
+        user_id: usize,
+        from_type: FundType,
+        to_type: FundType,
+        amount: &Self::BigUint,
+    ) {
+        if *amount > 0 {
+            self.fund_transformed_event(user_id, from_type, to_type, amount);
+        }
+    }
+
+    fn log_fund_transformation_per_user(
+        &self,
+        user_amounts: &[(usize, Self::BigUint)],
+        from_type: FundType,
+        to_type: FundType,
+    ) {
+        for (user_id, amount) in user_amounts.iter() {
+            self.log_fund_transformation(*user_id, from_type, to_type, amount);
+        }
+    }
+
+    /// Adds `amount` to the entry of `user_id`, keeping the users in the order they were first added.
+    fn add_user_amount(
+        &self,
+        user_amounts: &mut Vec<(usize, Self::BigUint)>,
+        user_id: usize,
+        amount: Self::BigUint,
+    ) {
+        if let Some((_, user_amount)) = user_amounts.iter_mut().find(|(id, _)| *id == user_id) {
+            *user_amount += &amount;
+        } else {
+            user_amounts.push((user_id, amount));
+        }
+    }
+
+    /// Same as `split_convert_max_by_type`, then logs the amount converted for each user.
+    /// All transformed funds are expected to end up in `target_type`.
+    fn split_convert_max_by_type_logged<F, I>(
+        &self,
+        remaining: &mut Self::BigUint,
+        source_type: FundType,
+        target_type: FundType,
+        direction: SwapDirection,
+        mut filter_transform: F,
+        interrupt: I,
+    ) -> Vec<usize>
+    where
+        F: FnMut(&FundItem<Self::BigUint>) -> Option<FundDescription>,
+        I: Fn() -> bool,
+    {
+        // follows the amount taken from each fund, see `decrease_fund_balance`
+        let mut left = remaining.clone();
+        let mut user_amounts = Vec::<(usize, Self::BigUint)>::new();
+        let affected_users = self.split_convert_max_by_type(
+            Some(remaining),
+            source_type,
+            direction,
+            |fund_item| {
+                let opt_transformed = filter_transform(fund_item);
+                if opt_transformed.is_some() {
+                    let amount = core::cmp::min(&fund_item.balance, &left).clone();
+                    left -= &amount;
+                    self.add_user_amount(&mut user_amounts, fund_item.user_id, amount);
+                }
+                opt_transformed
+            },
+            interrupt,
+            false,
+        );
+        self.log_fund_transformation_per_user(&user_amounts, source_type, target_type);
+        affected_users
+    }
+
     fn create_waiting(&self, user_id: usize, balance: Self::BigUint) {
         let current_bl_nonce = self.blockchain().get_block_nonce();
         self.increase_fund_balance(
@@ -28,7 +114,7 @@ pub trait FundTransformationsModule: fund_module::FundModule {
 
     fn swap_user_active_to_unstaked(&self, unstake_user_id: usize, amount: &mut Self::BigUint) {
         let current_bl_nonce = self.blockchain().get_block_nonce();
-        let _ = self.split_convert_max_by_user(
+        let transformed = self.split_convert_max_by_user(
             Some(amount),
             unstake_user_id,
             FundType::Active,
@@ -40,6 +126,39 @@ pub trait FundTransformationsModule: fund_module::FundModule {
             },
             || false,
         );
+        self.log_fund_transformation(
+            unstake_user_id,
+            FundType::Active,
+            FundType::UnStaked,
+            &transformed,
+        );
+    }
+
+    /// Inverse of `swap_user_active_to_unstaked`, converts the unstaked stake of a user
+    /// created at or after `min_created` back to active.
+    /// Yields the amount converted.
+    fn swap_user_unstaked_created_since_to_active(
+        &self,
+        user_id: usize,
+        min_created: u64,
+    ) -> Self::BigUint {
+        let transformed = self.split_convert_max_by_user(
+            None,
+            user_id,
+            FundType::UnStaked,
+            SwapDirection::Forwards,
+            |fund_desc| {
+                if let FundDescription::UnStaked { created } = fund_desc {
+                    if created >= min_created {
+                        return Some(FundDescription::Active);
+                    }
+                }
+                None
+            },
+            || false,
+        );
+        self.log_fund_transformation(user_id, FundType::UnStaked, FundType::Active, &transformed);
+        transformed
     }
 
     fn swap_waiting_to_active<I: Fn() -> bool>(
@@ -47,18 +166,18 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         remaining: &mut Self::BigUint,
         interrupt: I,
     ) -> Vec<usize> {
-        self.split_convert_max_by_type(
-            Some(remaining),
+        self.split_convert_max_by_type_logged(
+            remaining,
             FundType::Waiting,
+            FundType::Active,
             SwapDirection::Forwards,
             |_| Some(FundDescription::Active),
             interrupt,
-            false,
         )
     }
 
     fn swap_user_waiting_to_withdraw_only(&self, user_id: usize, remaining: &mut Self::BigUint) {
-        let _ = self.split_convert_max_by_user(
+        let transformed = self.split_convert_max_by_user(
             Some(remaining),
             user_id,
             FundType::Waiting,
@@ -66,9 +185,40 @@ pub trait FundTransformationsModule: fund_module::FundModule {
             |_| Some(FundDescription::WithdrawOnly),
             || false,
         );
+        self.log_fund_transformation(
+            user_id,
+            FundType::Waiting,
+            FundType::WithdrawOnly,
+            &transformed,
+        );
+    }
+
+    /// Turns all the withdraw-only funds of a user back into waiting stake.
+    fn swap_user_withdraw_only_to_waiting(&self, user_id: usize) -> Self::BigUint {
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        let transformed = self.split_convert_max_by_user(
+            None,
+            user_id,
+            FundType::WithdrawOnly,
+            SwapDirection::Forwards,
+            |_| {
+                Some(FundDescription::Waiting {
+                    created: current_bl_nonce,
+                })
+            },
+            || false,
+        );
+        self.log_fund_transformation(
+            user_id,
+            FundType::WithdrawOnly,
+            FundType::Waiting,
+            &transformed,
+        );
+        transformed
     }
 
     /// Applies transformation to all funds below given threshold.
+    /// All transformed funds are expected to end up in the same type.
     fn swap_dust<F, I>(
         &self,
         current_id: &mut usize,
@@ -80,19 +230,33 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         F: FnMut(&FundItem<Self::BigUint>) -> Option<FundDescription>,
         I: Fn() -> bool,
     {
+        let mut user_amounts = Vec::<(usize, Self::BigUint)>::new();
+        let mut opt_target_type: Option<FundType> = None;
         self.split_convert_max_by_type_with_checkpoint(
             current_id,
             source_type,
             SwapDirection::Backwards,
             |fund_item| {
                 if &fund_item.balance < dust_limit {
-                    filter_transform(fund_item)
+                    let opt_transformed = filter_transform(fund_item);
+                    if let Some(fund_desc) = &opt_transformed {
+                        self.add_user_amount(
+                            &mut user_amounts,
+                            fund_item.user_id,
+                            fund_item.balance.clone(),
+                        );
+                        opt_target_type = Some(fund_desc.fund_type());
+                    }
+                    opt_transformed
                 } else {
                     None
                 }
             },
             interrupt,
         );
+        if let Some(target_type) = opt_target_type {
+            self.log_fund_transformation_per_user(&user_amounts, source_type, target_type);
+        }
     }
 
     fn get_affected_users_of_swap_waiting_to_active<I: Fn() -> bool>(
@@ -118,9 +282,10 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         remaining: &mut Self::BigUint,
         interrupt: I,
     ) {
-        let _ = self.split_convert_max_by_type(
-            Some(remaining),
+        let _ = self.split_convert_max_by_type_logged(
+            remaining,
             FundType::UnStaked,
+            FundType::DeferredPayment,
             SwapDirection::Forwards,
             |fund_info| match fund_info.fund_desc {
                 FundDescription::UnStaked { created } => {
@@ -129,7 +294,28 @@ pub trait FundTransformationsModule: fund_module::FundModule {
                 _ => None,
             },
             interrupt,
-            false,
+        );
+    }
+
+    /// Inverse of `swap_unstaked_to_deferred_payment`, starting with the most recent funds.
+    /// The `created` nonce is kept, so the funds mature at the same block as before.
+    fn revert_deferred_to_unstaked<I: Fn() -> bool>(
+        &self,
+        remaining: &mut Self::BigUint,
+        interrupt: I,
+    ) {
+        let _ = self.split_convert_max_by_type_logged(
+            remaining,
+            FundType::DeferredPayment,
+            FundType::UnStaked,
+            SwapDirection::Backwards,
+            |fund_info| match fund_info.fund_desc {
+                FundDescription::DeferredPayment { created } => {
+                    Some(FundDescription::UnStaked { created })
+                }
+                _ => None,
+            },
+            interrupt,
         );
     }
 
@@ -139,9 +325,10 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         interrupt: I,
     ) {
         let current_bl_nonce = self.blockchain().get_block_nonce();
-        let _ = self.split_convert_max_by_type(
-            Some(remaining),
+        let _ = self.split_convert_max_by_type_logged(
+            remaining,
             FundType::Active,
+            FundType::DeferredPayment,
             SwapDirection::Backwards,
             |_| {
                 Some(FundDescription::DeferredPayment {
@@ -149,7 +336,6 @@ pub trait FundTransformationsModule: fund_module::FundModule {
                 })
             },
             interrupt,
-            false,
         );
     }
 
@@ -158,22 +344,80 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         user_id: usize,
         n_blocks_before_claim: u64,
         interrupt: I,
+    ) -> Self::BigUint {
+        self.swap_eligible_deferred_created_before_to_withdraw(
+            user_id,
+            n_blocks_before_claim,
+            u64::MAX,
+            interrupt,
+        )
+    }
+
+    /// Only converts the eligible deferred payments created strictly before `before_nonce`.
+    fn swap_eligible_deferred_created_before_to_withdraw<I: Fn() -> bool>(
+        &self,
+        user_id: usize,
+        n_blocks_before_claim: u64,
+        before_nonce: u64,
+        interrupt: I,
     ) -> Self::BigUint {
         let current_bl_nonce = self.blockchain().get_block_nonce();
-        self.split_convert_max_by_user(
+        let transformed = self.split_convert_max_by_user(
             None,
             user_id,
             FundType::DeferredPayment,
             SwapDirection::Forwards,
             |fund_desc| {
                 if let FundDescription::DeferredPayment { created } = fund_desc {
-                    if current_bl_nonce >= created + n_blocks_before_claim {
+                    if created < before_nonce
+                        && is_claimable(created, n_blocks_before_claim, current_bl_nonce)
+                    {
                         return Some(FundDescription::WithdrawOnly);
                     }
                 }
                 None
             },
             interrupt,
-        )
+        );
+        self.log_fund_transformation(
+            user_id,
+            FundType::DeferredPayment,
+            FundType::WithdrawOnly,
+            &transformed,
+        );
+        transformed
+    }
+
+    fn swap_eligible_deferred_to_waiting<I: Fn() -> bool>(
+        &self,
+        user_id: usize,
+        n_blocks_before_claim: u64,
+        interrupt: I,
+    ) -> Self::BigUint {
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        let transformed = self.split_convert_max_by_user(
+            None,
+            user_id,
+            FundType::DeferredPayment,
+            SwapDirection::Forwards,
+            |fund_desc| {
+                if let FundDescription::DeferredPayment { created } = fund_desc {
+                    if is_claimable(created, n_blocks_before_claim, current_bl_nonce) {
+                        return Some(FundDescription::Waiting {
+                            created: current_bl_nonce,
+                        });
+                    }
+                }
+                None
+            },
+            interrupt,
+        );
+        self.log_fund_transformation(
+            user_id,
+            FundType::DeferredPayment,
+            FundType::Waiting,
+            &transformed,
+        );
+        transformed
     }
 }
@@ -1,6 +1,6 @@
 elrond_wasm::imports!();
 
-use crate::fund_module::SwapDirection;
+use crate::fund_module::{is_claimable, SwapDirection};
 use crate::types::{FundDescription, FundType};
 
 use crate::fund_module;
@@ -16,6 +16,9 @@ pub const USER_STAKE_TOTALS_ID: usize = 0;
 /// Result type containing 5 numeric values, one for each stake type.
 pub type StakeByTypeResult<BigUint> = MultiResult5<BigUint, BigUint, BigUint, BigUint, BigUint>;
 
+/// Upper bound for the number of delegators yielded by `getTopDelegators`.
+pub const TOP_DELEGATORS_MAX_LIMIT: usize = 100;
+
 #[elrond_wasm_derive::module]
 pub trait FundViewModule: fund_module::FundModule + user_data::UserDataModule {
     // UTILS
@@ -158,11 +161,48 @@ pub trait FundViewModule: fund_module::FundModule + user_data::UserDataModule {
         }
     }
 
+    /// Same as `getUserStakeByType`, but each amount is preceded by its fund type.
+    /// Unstaked stake and deferred payments are included regardless of maturity.
+    #[view(getUserFundsBreakdown)]
+    fn get_user_funds_breakdown(
+        &self,
+        user_address: &Address,
+    ) -> MultiResultVec<MultiResult2<FundType, Self::BigUint>> {
+        let user_id = self.get_user_id(user_address);
+        if user_id == 0 {
+            FundType::ALL_TYPES
+                .iter()
+                .map(|&fund_type| (fund_type, Self::BigUint::zero()).into())
+                .collect::<Vec<_>>()
+                .into()
+        } else {
+            self.get_funds_breakdown(user_id)
+        }
+    }
+
+    fn get_funds_breakdown(
+        &self,
+        user_id: usize,
+    ) -> MultiResultVec<MultiResult2<FundType, Self::BigUint>> {
+        let mut result = Vec::with_capacity(FundType::ALL_TYPES.len());
+        for &fund_type in FundType::ALL_TYPES.iter() {
+            result.push((fund_type, self.get_user_stake_of_type(user_id, fund_type)).into());
+        }
+        result.into()
+    }
+
     #[view(getTotalStakeByType)]
     fn get_total_stake_by_type_endpoint(&self) -> StakeByTypeResult<Self::BigUint> {
         self.get_user_stake_by_type(USER_STAKE_TOTALS_ID)
     }
 
+    /// Same as `getTotalStakeByType`, but each total is preceded by its fund type,
+    /// the fund-side complement to `getNodeStateCounts`.
+    #[view(getStakeByPhase)]
+    fn get_stake_by_phase(&self) -> MultiResultVec<MultiResult2<FundType, Self::BigUint>> {
+        self.get_funds_breakdown(USER_STAKE_TOTALS_ID)
+    }
+
     // ALL USERS, ALL STAKE
 
     #[view(getAllUserStakeByType)]
@@ -172,6 +212,10 @@ pub trait FundViewModule: fund_module::FundModule + user_data::UserDataModule {
         let mut result: Vec<MultiResult2<Address, StakeByTypeResult<Self::BigUint>>> = Vec::new();
         let num_users = self.get_num_users();
         for user_id in 1..=num_users {
+            if self.is_empty_user_address(user_id) {
+                // purged user
+                continue;
+            }
             result.push(
                 (
                     self.get_user_address(user_id),
@@ -184,6 +228,44 @@ pub trait FundViewModule: fund_module::FundModule + user_data::UserDataModule {
         result.into()
     }
 
+    /// The delegators with the most waiting + active stake, largest first, at most `limit` of them.
+    /// `limit` is capped at `TOP_DELEGATORS_MAX_LIMIT`. Users with no waiting or active stake are left out.
+    /// Reads the stake of every user, so the gas cost grows with `getNumUsers`, regardless of `limit`.
+    #[view(getTopDelegators)]
+    fn get_top_delegators(
+        &self,
+        limit: usize,
+    ) -> MultiResultVec<MultiResult2<Address, Self::BigUint>> {
+        let limit = core::cmp::min(limit, TOP_DELEGATORS_MAX_LIMIT);
+        let mut top: Vec<(usize, Self::BigUint)> = Vec::new();
+        if limit > 0 {
+            let num_users = self.get_num_users();
+            for user_id in 1..=num_users {
+                let user_stake = self.get_user_stake_of_type(user_id, FundType::Waiting)
+                    + self.get_user_stake_of_type(user_id, FundType::Active);
+                if user_stake == 0 {
+                    continue;
+                }
+
+                // users with equal stake keep the order of their ids
+                let num_top = top.len();
+                let position = top
+                    .iter()
+                    .position(|(_, stake)| user_stake > *stake)
+                    .unwrap_or(num_top);
+                if position < limit {
+                    top.insert(position, (user_id, user_stake));
+                    top.truncate(limit);
+                }
+            }
+        }
+
+        top.into_iter()
+            .map(|(user_id, stake)| (self.get_user_address(user_id), stake).into())
+            .collect::<Vec<MultiResult2<Address, Self::BigUint>>>()
+            .into()
+    }
+
     // DEFERRED PAYMENT BREAKDOWN
 
     #[view(getUserDeferredPaymentList)]
@@ -218,7 +300,7 @@ pub trait FundViewModule: fund_module::FundModule + user_data::UserDataModule {
         let current_bl_nonce = self.blockchain().get_block_nonce();
         self.query_sum_funds_by_user_type(user_id, FundType::DeferredPayment, |fund_desc| {
             if let FundDescription::DeferredPayment { created } = fund_desc {
-                current_bl_nonce >= created + n_blocks_before_claim
+                is_claimable(created, n_blocks_before_claim, current_bl_nonce)
             } else {
                 false
             }
@@ -35,6 +35,96 @@ pub trait UserDataModule {
     #[storage_set("num_users")]
     fn set_num_users(&self, num_users: usize);
 
+    /// Block nonce when the user unstaked and their stake could not be immediately replaced.
+    /// Zero means there is no such pending request.
+    #[storage_get("u_unstake_req")]
+    fn get_user_unstake_request_nonce(&self, user_id: usize) -> u64;
+
+    #[storage_set("u_unstake_req")]
+    fn set_user_unstake_request_nonce(&self, user_id: usize, bl_nonce: u64);
+
+    /// Stake of the nodes the user unstaked with `forceUnstake` since their unstake request.
+    /// Cannot exceed their UnStaked funds, so each unstaked amount only forces out nodes once.
+    #[view(getUserForceUnstaked)]
+    #[storage_get("u_force_unstaked")]
+    fn get_user_force_unstaked(&self, user_id: usize) -> Self::BigUint;
+
+    #[storage_set("u_force_unstaked")]
+    fn set_user_force_unstaked(&self, user_id: usize, force_unstaked: &Self::BigUint);
+
+    #[storage_clear("u_force_unstaked")]
+    fn clear_user_force_unstaked(&self, user_id: usize);
+
+    /// Users that opted in get their matured deferred payments restaked instead of sitting idle.
+    #[storage_get("u_auto_restake")]
+    fn get_user_auto_restake(&self, user_id: usize) -> bool;
+
+    #[storage_set("u_auto_restake")]
+    fn set_user_auto_restake(&self, user_id: usize, auto_restake: bool);
+
+    /// Address where the rewards of the user are sent.
+    /// If empty, rewards are sent to the user address.
+    #[storage_get("u_reward_addr")]
+    fn get_user_reward_address(&self, user_id: usize) -> Address;
+
+    #[storage_is_empty("u_reward_addr")]
+    fn is_empty_user_reward_address(&self, user_id: usize) -> bool;
+
+    #[storage_set("u_reward_addr")]
+    fn set_user_reward_address(&self, user_id: usize, reward_address: &Address);
+
+    #[storage_clear("u_reward_addr")]
+    fn clear_user_reward_address(&self, user_id: usize);
+
+    /// Block nonce of the last stake, unstake, unbond, withdraw, restake or reward claim of the user.
+    #[storage_get("u_last_activity")]
+    fn get_user_last_activity_nonce(&self, user_id: usize) -> u64;
+
+    #[storage_set("u_last_activity")]
+    fn set_user_last_activity_nonce(&self, user_id: usize, bl_nonce: u64);
+
+    fn record_user_activity(&self, user_id: usize) {
+        self.set_user_last_activity_nonce(user_id, self.blockchain().get_block_nonce());
+    }
+
+    /// Zero for unknown users.
+    #[view(getUserLastActivity)]
+    fn get_user_last_activity(&self, user_address: Address) -> u64 {
+        let user_id = self.get_user_id(&user_address);
+        if user_id == 0 {
+            0
+        } else {
+            self.get_user_last_activity_nonce(user_id)
+        }
+    }
+
+    #[storage_clear("user_id")]
+    fn clear_user_id(&self, address: &Address);
+
+    #[storage_clear("user_address")]
+    fn clear_user_address(&self, user_id: usize);
+
+    #[storage_clear("u_unstake_req")]
+    fn clear_user_unstake_request_nonce(&self, user_id: usize);
+
+    #[storage_clear("u_auto_restake")]
+    fn clear_user_auto_restake(&self, user_id: usize);
+
+    #[storage_clear("u_last_activity")]
+    fn clear_user_last_activity_nonce(&self, user_id: usize);
+
+    /// Deletes the data of the user kept in this module.
+    /// The user id is not reused, the address gets a new one if it comes back.
+    fn clear_user_data(&self, user_id: usize, address: &Address) {
+        self.clear_user_id(address);
+        self.clear_user_address(user_id);
+        self.clear_user_unstake_request_nonce(user_id);
+        self.clear_user_force_unstaked(user_id);
+        self.clear_user_auto_restake(user_id);
+        self.clear_user_reward_address(user_id);
+        self.clear_user_last_activity_nonce(user_id);
+    }
+
     // creates new user id
     fn new_user(&self) -> usize {
         let mut num_users = self.get_num_users();
@@ -1,8 +1,11 @@
-use user_fund_storage::fund_module::FundModule;
+use user_fund_storage::fund_module::{is_claimable, FundModule};
 use user_fund_storage::fund_transf_module::FundTransformationsModule;
-use user_fund_storage::types::FundType;
+use user_fund_storage::fund_view_module::FundViewModule;
+use user_fund_storage::types::{FundDescription, FundType};
+use user_fund_storage::user_data::UserDataModule;
 
 use elrond_wasm::api::BigUintApi;
+use elrond_wasm::types::Address;
 use elrond_wasm_debug::api::RustBigUint;
 use elrond_wasm_debug::TxContext;
 
@@ -220,3 +223,155 @@ fn test_full_cycle_1() {
         module.count_fund_items_by_user_type(user_id, FundType::WithdrawOnly, |_| true)
     );
 }
+
+#[test]
+fn test_revert_deferred_to_unstaked() {
+    let module = user_fund_storage::fund_transf_module::contract_obj(TxContext::dummy());
+
+    module.increase_fund_balance(
+        2,
+        FundDescription::DeferredPayment { created: 5 },
+        3000u32.into(),
+    );
+    module.increase_fund_balance(
+        3,
+        FundDescription::DeferredPayment { created: 7 },
+        2000u32.into(),
+    );
+
+    // DeferredPayment -> UnStaked, most recent first
+    let mut amount = RustBigUint::from(2500u32);
+    module.revert_deferred_to_unstaked(&mut amount, || false);
+    assert_eq!(amount, RustBigUint::zero());
+
+    fund_module_check::check_consistency(&module, 4);
+    assert_eq!(
+        RustBigUint::from(2500u32),
+        module.query_sum_funds_by_type(FundType::DeferredPayment, |_, _| true)
+    );
+    assert_eq!(
+        RustBigUint::from(2500u32),
+        module.query_sum_funds_by_type(FundType::UnStaked, |_, _| true)
+    );
+
+    // created nonces are kept
+    assert_eq!(
+        RustBigUint::from(500u32),
+        module.query_sum_funds_by_user_type(2, FundType::UnStaked, |fund_desc| {
+            fund_desc == FundDescription::UnStaked { created: 5 }
+        })
+    );
+    assert_eq!(
+        RustBigUint::from(2000u32),
+        module.query_sum_funds_by_user_type(3, FundType::UnStaked, |fund_desc| {
+            fund_desc == FundDescription::UnStaked { created: 7 }
+        })
+    );
+    assert_eq!(
+        RustBigUint::from(2500u32),
+        module.query_sum_funds_by_user_type(2, FundType::DeferredPayment, |_| true)
+    );
+}
+
+#[test]
+fn test_query_sum_funds_ahead_of_user() {
+    let module = user_fund_storage::fund_transf_module::contract_obj(TxContext::dummy());
+
+    module.create_waiting(2, 1000u32.into());
+    module.create_waiting(3, 2000u32.into());
+    module.create_waiting(2, 3000u32.into());
+    module.create_waiting(4, 4000u32.into());
+
+    assert_eq!(
+        Some(RustBigUint::zero()),
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 2)
+    );
+    assert_eq!(
+        Some(RustBigUint::from(1000u32)),
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 3)
+    );
+    assert_eq!(
+        Some(RustBigUint::from(6000u32)),
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 4)
+    );
+    assert_eq!(
+        None,
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 5)
+    );
+
+    // the first user leaves the queue
+    let mut amount = RustBigUint::from(4000u32);
+    module.swap_user_waiting_to_withdraw_only(2, &mut amount);
+    assert_eq!(amount, RustBigUint::zero());
+
+    fund_module_check::check_consistency(&module, 5);
+    assert_eq!(
+        Some(RustBigUint::zero()),
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 3)
+    );
+    assert_eq!(
+        Some(RustBigUint::from(2000u32)),
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 4)
+    );
+    assert_eq!(
+        None,
+        module.query_sum_funds_ahead_of_user(FundType::Waiting, 2)
+    );
+}
+
+#[test]
+fn test_claim_period_overflow() {
+    let module = user_fund_storage::fund_transf_module::contract_obj(TxContext::dummy());
+
+    module.increase_fund_balance(
+        2,
+        FundDescription::DeferredPayment { created: 5 },
+        3000u32.into(),
+    );
+
+    // created + n_blocks_before_claim overflows, so the funds must never become claimable
+    let transformed = module.swap_eligible_deferred_to_withdraw(2, u64::MAX - 2, || false);
+    assert_eq!(transformed, RustBigUint::zero());
+
+    fund_module_check::check_consistency(&module, 3);
+    assert_eq!(
+        RustBigUint::from(3000u32),
+        module.query_sum_funds_by_user_type(2, FundType::DeferredPayment, |_| true)
+    );
+
+    assert!(!is_claimable(5, u64::MAX - 2, u64::MAX));
+    assert!(is_claimable(5, u64::MAX - 5, u64::MAX));
+}
+
+#[test]
+fn test_get_top_delegators() {
+    let module = user_fund_storage::fund_view_module::contract_obj(TxContext::dummy());
+
+    module.set_num_users(5);
+    for user_id in 1..=5 {
+        module.set_user_address(user_id, &Address::from([user_id as u8; 32]));
+    }
+    module.increase_fund_balance(2, FundDescription::Waiting { created: 0 }, 1000u32.into());
+    module.increase_fund_balance(3, FundDescription::Active, 3000u32.into());
+    module.increase_fund_balance(4, FundDescription::Active, 2000u32.into());
+    module.increase_fund_balance(4, FundDescription::Waiting { created: 0 }, 1000u32.into());
+    // not counted
+    module.increase_fund_balance(5, FundDescription::WithdrawOnly, 5000u32.into());
+
+    let top: Vec<(Address, RustBigUint)> = module
+        .get_top_delegators(2)
+        .into_vec()
+        .into_iter()
+        .map(|entry| entry.into_tuple())
+        .collect();
+    assert_eq!(
+        top,
+        vec![
+            (Address::from([3u8; 32]), RustBigUint::from(3000u32)),
+            (Address::from([4u8; 32]), RustBigUint::from(3000u32)),
+        ]
+    );
+
+    assert_eq!(module.get_top_delegators(10).into_vec().len(), 3);
+    assert_eq!(module.get_top_delegators(0).into_vec().len(), 0);
+}
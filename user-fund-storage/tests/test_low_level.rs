@@ -573,3 +573,84 @@ fn test_user_swap(direction: SwapDirection) {
         fund_module.query_sum_funds_by_user_type(user_id_2, FundType::Waiting, |_| true)
     );
 }
+
+#[test]
+fn test_compact_user_funds() {
+    let fund_module = user_fund_storage::fund_module::contract_obj(TxContext::dummy());
+    let user_id_1 = 1;
+    let user_id_2 = 2;
+
+    // 50 fragments for user 1, interleaved with funds of user 2
+    for i in 0..50u32 {
+        fund_module.increase_fund_balance(user_id_1, FundDescription::Active, (100 + i).into());
+        if i % 10 == 0 {
+            fund_module.increase_fund_balance(user_id_2, FundDescription::Active, 7u32.into());
+        }
+    }
+    for created in &[WAITING_CREATED, WAITING_CREATED, WAITING_CREATED + 1] {
+        fund_module.increase_fund_balance(
+            user_id_1,
+            FundDescription::Waiting { created: *created },
+            10u32.into(),
+        );
+    }
+
+    assert_eq!(
+        50,
+        fund_module.count_fund_items_by_user_type(user_id_1, FundType::Active, |_| true)
+    );
+    let max_id_before = fund_module.get_fund_max_id();
+
+    let removed = fund_module.compact_user_funds(user_id_1, FundType::Active);
+    assert_eq!(removed, 49);
+
+    fund_module_check::check_consistency(&fund_module, 3);
+    assert_eq!(
+        1,
+        fund_module.count_fund_items_by_user_type(user_id_1, FundType::Active, |_| true)
+    );
+    assert_eq!(
+        RustBigUint::from(6225u32),
+        fund_module.query_sum_funds_by_user_type(user_id_1, FundType::Active, |_| true)
+    );
+    assert_eq!(
+        5,
+        fund_module.count_fund_items_by_user_type(user_id_2, FundType::Active, |_| true)
+    );
+    assert_eq!(
+        RustBigUint::from(6260u32),
+        fund_module.query_sum_funds_by_type(FundType::Active, |_, _| true)
+    );
+
+    // the storage of the removed items was freed, the merged balance sits in the first item
+    let mut num_items_in_storage = 0;
+    for id in 1..=max_id_before {
+        if !fund_module.fund_by_id(id).is_empty() {
+            num_items_in_storage += 1;
+        }
+    }
+    assert_eq!(num_items_in_storage, 1 + 5 + 3);
+    assert_eq!(
+        RustBigUint::from(6225u32),
+        fund_module.fund_by_id(1).get().balance
+    );
+
+    // waiting funds only merge if created in the same block
+    let removed = fund_module.compact_user_funds(user_id_1, FundType::Waiting);
+    assert_eq!(removed, 1);
+
+    fund_module_check::check_consistency(&fund_module, 3);
+    assert_eq!(
+        2,
+        fund_module.count_fund_items_by_user_type(user_id_1, FundType::Waiting, |_| true)
+    );
+    assert_eq!(
+        RustBigUint::from(20u32),
+        fund_module.query_sum_funds_by_user_type(user_id_1, FundType::Waiting, |fund_desc| {
+            fund_desc
+                == FundDescription::Waiting {
+                    created: WAITING_CREATED,
+                }
+        })
+    );
+}